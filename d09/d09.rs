@@ -1,5 +1,3 @@
-#![feature(assert_matches)]
-
 use std::assert_matches;
 use std::fmt::{Display, Formatter, Write};
 use std::iter::repeat_n;
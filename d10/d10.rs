@@ -1,3 +1,4 @@
+use aoc2016::graph::count_dag_paths;
 use aoc2016::grid::{Grid, Pos};
 use std::collections::HashSet;
 
@@ -48,41 +49,23 @@ fn trailheads(grid: &Grid<u8>) -> (usize, Grid<usize>) {
 }
 
 fn trailhead_ratings(grid: &Grid<u8>) -> (usize, Grid<usize>) {
-    fn paths(grid: &Grid<u8>, cache: &mut Grid<Option<usize>>, p @ [x, y]: Pos) -> usize {
-        if let Some(c) = cache[p] {
-            return c;
-        }
-        let v = grid[p];
-        if !(0..=9).contains(&v) {
-            cache[p] = Some(0);
-            return 0;
-        }
-        if v == 9 {
-            cache[p] = Some(1);
-            return 1;
-        }
-        let mut total = 0;
-        for n in [[x + 1, y], [x, y + 1], [x - 1, y], [x, y - 1]] {
-            if grid.is_inside(n) && grid[n] == v + 1 {
-                total += paths(grid, cache, n);
-            }
-        }
-        cache[p] = Some(total);
-        total
-    }
-
-    let mut total = 0;
-    let mut cache = Grid::new(grid.size);
-    for i in 0..grid.len() {
-        if grid[i] == 0 {
-            total += paths(
-                grid,
-                &mut cache,
-                [(i % grid.width()) as isize, (i / grid.height()) as isize],
-            );
-        }
+    let trailheads: Vec<_> = grid.positions().filter(|&p| grid[p] == 0).collect();
+    let counts = count_dag_paths(
+        trailheads.iter().copied(),
+        |&p| grid[p] == 9,
+        |&[x, y]| {
+            let v = grid[[x, y]];
+            [[x + 1, y], [x, y + 1], [x - 1, y], [x, y - 1]]
+                .into_iter()
+                .filter(move |&n| v < 9 && grid.is_inside(n) && grid[n] == v + 1)
+        },
+    );
+    let total = trailheads.iter().map(|p| counts[p]).sum::<u128>();
+    let mut ratings = Grid::new(grid.size);
+    for (p, c) in counts {
+        ratings[p] = c as usize;
     }
-    (total, cache.map(Option::unwrap_or_default))
+    (total as usize, ratings)
 }
 
 fn main() {
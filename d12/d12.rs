@@ -77,6 +77,7 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2016::graph::label_regions;
 
     #[test]
     fn test_areas() {
@@ -114,6 +115,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_label_regions() {
+        let grid = Grid::from([*b"AAAA", *b"BBCD", *b"BBCC", *b"EEEC"]);
+        let (labels, count) = label_regions(&grid, |a, b| a == b);
+        let mut sizes = vec![0; count];
+        labels.iter().for_each(|&l| sizes[l] += 1);
+        let areas: Vec<_> = get_areas(&grid, false).iter().map(|a| a.area).collect();
+        assert_eq!(sizes, areas);
+    }
+
     #[test]
     fn test_areas_bulk() {
         let grid = Grid::from([*b"AAAA", *b"BBCD", *b"BBCC", *b"EEEC"]);
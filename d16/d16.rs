@@ -1,8 +1,7 @@
-use aoc2016::graph::{NoPathFound, a_star_rev};
+use aoc2016::graph::{BucketQueue, NoPathFound, bucket_search};
 use aoc2016::grid::{Grid, Pos};
 use std::fmt::{Display, Formatter, Write};
 use std::hash::Hash;
-use std::iter::once;
 use std::str::FromStr;
 use vecmath::{vec2_add, vec2_dot};
 
@@ -134,7 +133,7 @@ fn find_cheapest_path_from(
     start_dir: Dir,
     goal: Pos,
 ) -> Result<(Vec<Pos>, i64), NoPathFound> {
-    let (path, last) = a_star_rev(
+    let path = bucket_search(
         &(start, start_dir),
         |&(p, _)| p == goal,
         |&(p, dir)| {
@@ -148,23 +147,18 @@ fn find_cheapest_path_from(
                     Some(((n_pos, n_dir), dir.abs_angle(&n_dir)))
                 })
         },
-        |_| 0,
         |_, angle, _| angle.cost() + 1,
+        BucketQueue::Auto,
     )?;
-    let total_cost = path.iter().map(|(_, angle)| angle.cost() + 1).sum();
+    let total_cost = path.cost();
     Ok((
-        path.into_iter()
-            .rev()
-            .map(|((p, _), _)| p)
-            .chain(once(last.0))
-            .collect(),
+        path.into_nodes().into_iter().map(|(p, _)| p).collect(),
         total_cost,
     ))
 }
 
 fn count_paths_with_score_at_most(field: &Field, max_score: i64) -> usize {
     let mut used = Grid::new(field.grid.size);
-    static mut DEBUG_COUNT: u64 = 0;
     fn recurse(
         grid: &Grid<Cell>,
         used: &mut Grid<bool>,
@@ -174,11 +168,6 @@ fn count_paths_with_score_at_most(field: &Field, max_score: i64) -> usize {
         cost: i64,
         max_score: i64,
     ) {
-        unsafe {
-            DEBUG_COUNT += 1;
-            let dc = DEBUG_COUNT;
-            println!("{}", dc);
-        }
         used[pos] = true;
         for next_dir in [Dir::North, Dir::East, Dir::South, Dir::West] {
             let ang = dir.abs_angle(&next_dir);
@@ -198,9 +187,6 @@ fn count_paths_with_score_at_most(field: &Field, max_score: i64) -> usize {
             recurse(grid, used, next_pos, next_dir, goal, cost, max_score);
         }
     }
-    unsafe {
-        DEBUG_COUNT = 0;
-    }
     recurse(
         &field.grid,
         &mut used,
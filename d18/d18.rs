@@ -1,6 +1,5 @@
-use aoc2016::graph::{NoPathFound, a_star_rev};
+use aoc2016::graph::{NoPathFound, a_star_indexed, first_disconnecting_insertion};
 use aoc2016::grid::{Grid, Pos};
-use std::iter::once;
 
 fn parse_coords(s: &str) -> impl Iterator<Item = Pos> {
     s.lines()
@@ -33,8 +32,9 @@ fn find_path(grid: &Grid<Cell>, start: Pos, goal: Pos) -> Result<Vec<Pos>, NoPat
     fn hamming([ax, ay]: Pos, [bx, by]: Pos) -> usize {
         ax.abs_diff(bx) + ay.abs_diff(by)
     }
-    Ok(a_star_rev(
+    Ok(a_star_indexed(
         &start,
+        grid.size,
         |&n| n == goal,
         |&[x, y]| {
             [[x + 1, y], [x, y + 1], [x - 1, y], [x, y - 1]]
@@ -45,33 +45,12 @@ fn find_path(grid: &Grid<Cell>, start: Pos, goal: Pos) -> Result<Vec<Pos>, NoPat
         |&n| hamming(n, goal) as i64,
         |&a, (), &b| hamming(a, b) as i64,
     )?
-    .0
-    .into_iter()
-    .rev()
-    .map(|(p, _)| p)
-    .chain(once(goal))
-    .collect())
+    .into_nodes())
 }
 
 fn find_first_blocking_byte(grid: &Grid<Cell>, coords: &[Pos], start: Pos, goal: Pos) -> Pos {
-    let mut begin = 0;
-    let mut end = coords.len();
-    while begin + 1 < end {
-        let current = (begin + end - 1) / 2;
-        let mut grid = grid.clone();
-        coords
-            .iter()
-            .copied()
-            .take(current)
-            .for_each(|p| grid[p] = Cell::Byte);
-
-        if find_path(&grid, start, goal).is_ok() {
-            begin = current + 1;
-        } else {
-            end = current + 1;
-        }
-    }
-    coords[begin - 1]
+    let i = first_disconnecting_insertion(grid, |_, &c| c != Cell::Byte, coords, start, goal);
+    coords[i.expect("no byte disconnects start from goal")]
 }
 
 fn main() {
@@ -95,6 +74,7 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2016::graph::{IncrementalPlanner, grid_a_star, jump_point_search};
 
     #[test]
     fn test_parse() {
@@ -130,4 +110,38 @@ mod tests {
         let result = find_first_blocking_byte(&grid, &coords, [0, 0], [6, 6]);
         assert_eq!(result, [6, 1]);
     }
+
+    #[test]
+    fn test_jump_point_search() {
+        let mut grid = Grid::<Cell>::new([71, 71]);
+        parse_coords(include_str!("input.txt"))
+            .take(1024)
+            .for_each(|p| grid[p] = Cell::Byte);
+        let open = |_, &c: &Cell| c != Cell::Byte;
+        let expected = grid_a_star(&grid, open, [0, 0], [70, 70], ()).unwrap();
+        let path = jump_point_search(&grid, open, [0, 0], [70, 70]).unwrap();
+        assert_eq!(path.cost(), expected.cost());
+        assert_eq!(
+            path.nodes().len(),
+            find_path(&grid, [0, 0], [70, 70]).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn test_incremental_planner() {
+        let grid = Grid::<Cell>::new([71, 71]);
+        let coords = parse_coords(include_str!("input.txt")).collect::<Vec<_>>();
+        let mut planner = IncrementalPlanner::new(&grid, |_, _| true, [0, 0], [70, 70]);
+        let first = coords
+            .iter()
+            .find(|&&p| {
+                planner.block(p);
+                planner.cost().is_none()
+            })
+            .unwrap();
+        assert_eq!(
+            *first,
+            find_first_blocking_byte(&grid, &coords, [0, 0], [70, 70])
+        );
+    }
 }
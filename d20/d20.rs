@@ -257,6 +257,7 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2016::graph::{grid_a_star, jump_point_search};
 
     #[test]
     fn test_find_cheats() {
@@ -272,4 +273,13 @@ mod tests {
         let c = find_cheats_at_least(&distances, 20, 1);
         println!("{:?}", c);
     }
+
+    #[test]
+    fn test_jump_point_search() {
+        let Input { grid, start, goal } = include_str!("input.txt").parse().unwrap();
+        let open = |_, &c: &Cell| c != Cell::Wall;
+        let expected = grid_a_star(&grid, open, start, goal, ()).unwrap();
+        let path = jump_point_search(&grid, open, start, goal).unwrap();
+        assert_eq!(path.cost(), expected.cost());
+    }
 }
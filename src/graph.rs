@@ -1,14 +1,68 @@
 pub use a_star::NoPathFound;
-pub use a_star::a_star_rev;
-use std::cmp;
-use std::collections::HashMap;
+pub use a_star::{
+    a_star, a_star_limited, a_star_observed, a_star_pruned, a_star_rev, a_star_rev_limited,
+    a_star_rev_observed, a_star_rev_pruned,
+};
+pub use adjacency::{Graph, NodeId};
 
-pub use bfs_impl::bfs;
+pub use bfs_impl::{bfs, bfs_limited, bfs_observed};
+pub use bidirectional::{bidirectional_bfs, bidirectional_dijkstra};
+pub use buckets::{BucketQueue, bucket_search, bucket_search_rev, zero_one_bfs, zero_one_bfs_rev};
+pub use cliques::CliqueGraph;
+pub use contraction::{Corridor, contract_corridors, expand_corridors};
+pub use cost::{Cost, OrdF64};
+pub use counting::{count_dag_paths, count_shortest_paths, count_shortest_paths_bfs};
+pub use deepening::{depth_limited_search, ida_star};
+pub use dot::{DotOptions, to_dot, to_dot_with};
+pub use flow::{FlowNetwork, INFINITE_CAPACITY, MaxFlow, grid_flow_network, grid_min_vertex_cut};
+pub use incremental::IncrementalPlanner;
+pub use indexed::{
+    NodeIndex, a_star_indexed, a_star_indexed_limited, a_star_indexed_rev,
+    a_star_indexed_rev_limited,
+};
+pub use jps::{grid_a_star, jump_point_search, jump_point_search_observed};
+pub use limits::{CancellationToken, Limit, SearchAborted, SearchError, SearchLimits};
+pub use longest::{longest_dag_path, longest_simple_path};
+pub use matching::{Assignment, hopcroft_karp, hungarian};
+pub use observer::{SearchObserver, SearchStats};
+pub use path::Path;
+pub use scc::{condensation, strongly_connected_components};
+pub use tsp::{
+    Route, hamiltonian_path, hamiltonian_path_to, inv_tsp, tsp, tsp_heuristic, tsp_improve,
+    tsp_nearest_neighbor,
+};
+pub use union_find::{UnionFind, first_disconnecting_insertion, label_regions};
+pub use yen::k_shortest_paths;
+
+mod adjacency;
+mod bidirectional;
+mod buckets;
+mod cliques;
+mod contraction;
+mod cost;
+mod counting;
+mod deepening;
+mod dot;
+mod flow;
+mod incremental;
+mod indexed;
+mod jps;
+mod limits;
+mod longest;
+mod matching;
+mod observer;
+mod path;
+mod scc;
+mod tsp;
+mod union_find;
+mod yen;
 
 mod a_star {
+    use super::{Cost, Limit, Path, SearchAborted, SearchError, SearchLimits, SearchObserver};
     use std::collections::{HashMap, HashSet};
     use std::fmt::Formatter;
     use std::hash::Hash;
+    use std::time::Instant;
     use std::{error, fmt};
 
     #[derive(Eq, PartialEq, Copy, Clone, Debug, Default)]
@@ -22,30 +76,230 @@ mod a_star {
 
     impl error::Error for NoPathFound {}
 
-    /// returns the path in reverse order because it might be needed, and it would be inefficient to
-    /// reverse it twice in that case.
-    pub fn a_star_rev<Node, Edge, Neighbors>(
+    /// With the `check-heuristic` feature, panics unless `h(from) <= d + h(to)` for an edge of
+    /// length `d`. Called right after the edge is passed to [SearchObserver::on_relax], so that an
+    /// observer can tell which nodes it was, without requiring them to be [Debug](fmt::Debug).
+    #[cfg(feature = "check-heuristic")]
+    pub(super) fn check_consistent<C: Cost>(h_from: C, d: C, h_to: C) {
+        assert!(
+            h_from <= d.plus(h_to),
+            "heuristic is inconsistent on the edge last passed to on_relax"
+        );
+    }
+
+    /// With the `check-heuristic` feature, panics unless the heuristic `h` of a goal is zero.
+    #[cfg(feature = "check-heuristic")]
+    pub(super) fn check_goal<C: Cost>(h: C) {
+        assert!(h == C::zero(), "heuristic is not zero at the goal");
+    }
+
+    /// A* search from `start` to the nearest node for which `is_goal` holds. `heuristic` must
+    /// never overestimate the remaining cost.
+    pub fn a_star<Node, Edge, Neighbors, C>(
+        start: &Node,
+        is_goal: impl Fn(&Node) -> bool,
+        get_neighbors: impl Fn(&Node) -> Neighbors,
+        heuristic: impl Fn(&Node) -> C,
+        distance: impl Fn(&Node, &Edge, &Node) -> C,
+    ) -> Result<Path<Node, Edge, C>, NoPathFound>
+    where
+        Node: Clone + Eq + Hash,
+        Edge: Clone,
+        Neighbors: IntoIterator<Item = (Node, Edge)>,
+        C: Cost,
+    {
+        let (rev, goal) = a_star_rev(start, is_goal, get_neighbors, heuristic, &distance)?;
+        Ok(Path::from_rev(rev, goal, distance))
+    }
+
+    /// [a_star] whose heuristic returns `None` for nodes from which no goal can be reached.
+    pub fn a_star_pruned<Node, Edge, Neighbors, C>(
+        start: &Node,
+        is_goal: impl Fn(&Node) -> bool,
+        get_neighbors: impl Fn(&Node) -> Neighbors,
+        heuristic: impl Fn(&Node) -> Option<C>,
+        distance: impl Fn(&Node, &Edge, &Node) -> C,
+    ) -> Result<Path<Node, Edge, C>, NoPathFound>
+    where
+        Node: Clone + Eq + Hash,
+        Edge: Clone,
+        Neighbors: IntoIterator<Item = (Node, Edge)>,
+        C: Cost,
+    {
+        let (rev, goal) = a_star_rev_pruned(start, is_goal, get_neighbors, heuristic, &distance)?;
+        Ok(Path::from_rev(rev, goal, distance))
+    }
+
+    /// [a_star], reporting its progress to `observer`.
+    pub fn a_star_observed<Node, Edge, Neighbors, C>(
         start: &Node,
         is_goal: impl Fn(&Node) -> bool,
         get_neighbors: impl Fn(&Node) -> Neighbors,
-        heuristic: impl Fn(&Node) -> i64,
-        distance: impl Fn(&Node, &Edge, &Node) -> i64,
+        heuristic: impl Fn(&Node) -> C,
+        distance: impl Fn(&Node, &Edge, &Node) -> C,
+        observer: impl SearchObserver<Node>,
+    ) -> Result<Path<Node, Edge, C>, NoPathFound>
+    where
+        Node: Clone + Eq + Hash,
+        Edge: Clone,
+        Neighbors: IntoIterator<Item = (Node, Edge)>,
+        C: Cost,
+    {
+        let (rev, goal) = a_star_rev_observed(
+            start,
+            is_goal,
+            get_neighbors,
+            heuristic,
+            &distance,
+            observer,
+        )?;
+        Ok(Path::from_rev(rev, goal, distance))
+    }
+
+    /// [a_star_observed] that gives up once one of the `limits` is reached, see
+    /// [a_star_rev_limited].
+    pub fn a_star_limited<Node, Edge, Neighbors, C>(
+        start: &Node,
+        is_goal: impl Fn(&Node) -> bool,
+        get_neighbors: impl Fn(&Node) -> Neighbors,
+        heuristic: impl Fn(&Node) -> Option<C>,
+        distance: impl Fn(&Node, &Edge, &Node) -> C,
+        limits: &SearchLimits<C>,
+        observer: impl SearchObserver<Node>,
+    ) -> Result<Path<Node, Edge, C>, SearchError<Node>>
+    where
+        Node: Clone + Eq + Hash,
+        Edge: Clone,
+        Neighbors: IntoIterator<Item = (Node, Edge)>,
+        C: Cost,
+    {
+        let (rev, goal) = a_star_rev_limited(
+            start,
+            is_goal,
+            get_neighbors,
+            heuristic,
+            &distance,
+            limits,
+            observer,
+        )?;
+        Ok(Path::from_rev(rev, goal, distance))
+    }
+
+    /// [a_star], returning the path in reverse order without its costs, because that might be
+    /// needed, and it would be inefficient to reverse it twice in that case.
+    pub fn a_star_rev<Node, Edge, Neighbors, C>(
+        start: &Node,
+        is_goal: impl Fn(&Node) -> bool,
+        get_neighbors: impl Fn(&Node) -> Neighbors,
+        heuristic: impl Fn(&Node) -> C,
+        distance: impl Fn(&Node, &Edge, &Node) -> C,
     ) -> Result<(Vec<(Node, Edge)>, Node), NoPathFound>
     where
         Node: Clone + Eq + Hash,
         Edge: Clone,
         Neighbors: IntoIterator<Item = (Node, Edge)>,
+        C: Cost,
+    {
+        a_star_rev_observed(start, is_goal, get_neighbors, heuristic, distance, ())
+    }
+
+    /// [a_star_rev] whose heuristic returns `None` for nodes from which no goal can be reached.
+    /// Those nodes are never queued.
+    pub fn a_star_rev_pruned<Node, Edge, Neighbors, C>(
+        start: &Node,
+        is_goal: impl Fn(&Node) -> bool,
+        get_neighbors: impl Fn(&Node) -> Neighbors,
+        heuristic: impl Fn(&Node) -> Option<C>,
+        distance: impl Fn(&Node, &Edge, &Node) -> C,
+    ) -> Result<(Vec<(Node, Edge)>, Node), NoPathFound>
+    where
+        Node: Clone + Eq + Hash,
+        Edge: Clone,
+        Neighbors: IntoIterator<Item = (Node, Edge)>,
+        C: Cost,
+    {
+        a_star_rev_limited(
+            start,
+            is_goal,
+            get_neighbors,
+            heuristic,
+            distance,
+            &SearchLimits::default(),
+            (),
+        )
+        .map_err(|_| NoPathFound)
+    }
+
+    /// [a_star_rev], reporting its progress to `observer`.
+    pub fn a_star_rev_observed<Node, Edge, Neighbors, C>(
+        start: &Node,
+        is_goal: impl Fn(&Node) -> bool,
+        get_neighbors: impl Fn(&Node) -> Neighbors,
+        heuristic: impl Fn(&Node) -> C,
+        distance: impl Fn(&Node, &Edge, &Node) -> C,
+        observer: impl SearchObserver<Node>,
+    ) -> Result<(Vec<(Node, Edge)>, Node), NoPathFound>
+    where
+        Node: Clone + Eq + Hash,
+        Edge: Clone,
+        Neighbors: IntoIterator<Item = (Node, Edge)>,
+        C: Cost,
+    {
+        a_star_rev_limited(
+            start,
+            is_goal,
+            get_neighbors,
+            |n| Some(heuristic(n)),
+            distance,
+            &SearchLimits::default(),
+            observer,
+        )
+        .map_err(|_| NoPathFound)
+    }
+
+    /// [a_star_rev_observed] that gives up once one of the `limits` is reached. Like
+    /// [a_star_rev_pruned], the heuristic may return `None` to prune a node.
+    ///
+    /// With the `check-heuristic` feature, panics if the heuristic is not zero at the goal, or
+    /// if `h(a) > distance(a, b) + h(b)` for an edge that is relaxed. All the A* functions of
+    /// this module run through here, the ones over a [NodeIndex](super::NodeIndex) through
+    /// [a_star_indexed_rev_limited](super::a_star_indexed_rev_limited), which checks the same,
+    /// as does [ida_star](super::ida_star). The other searches take no heuristic.
+    #[allow(clippy::type_complexity)]
+    pub fn a_star_rev_limited<Node, Edge, Neighbors, C>(
+        start: &Node,
+        is_goal: impl Fn(&Node) -> bool,
+        get_neighbors: impl Fn(&Node) -> Neighbors,
+        heuristic: impl Fn(&Node) -> Option<C>,
+        distance: impl Fn(&Node, &Edge, &Node) -> C,
+        limits: &SearchLimits<C>,
+        mut observer: impl SearchObserver<Node>,
+    ) -> Result<(Vec<(Node, Edge)>, Node), SearchError<Node>>
+    where
+        Node: Clone + Eq + Hash,
+        Edge: Clone,
+        Neighbors: IntoIterator<Item = (Node, Edge)>,
+        C: Cost,
     {
+        let started = Instant::now();
+        let mut expanded = 0;
+        let Some(h) = heuristic(start) else {
+            observer.on_finish(false);
+            return Err(SearchError::NoPathFound);
+        };
+        let mut best = (h, start.clone());
+        let mut pruned = false;
         let mut open_set = HashSet::from([start.clone()]);
         let mut came_from = HashMap::<_, (Node, Edge)>::new();
-        let mut g_score = HashMap::from([(start.clone(), 0i64)]);
-        let mut f_score = HashMap::from([(start.clone(), heuristic(start))]);
+        let mut g_score = HashMap::from([(start.clone(), C::zero())]);
+        let mut f_score = HashMap::from([(start.clone(), h)]);
 
-        while let Some(current) = open_set
-            .iter()
-            .min_by_key(|&s| f_score.get(s).copied().unwrap_or(i64::MAX))
-        {
+        while let Some(current) = open_set.iter().min_by_key(|&s| f_score[s]) {
             if is_goal(current) {
+                #[cfg(feature = "check-heuristic")]
+                if let Some(h) = heuristic(current) {
+                    check_goal(h);
+                }
                 let mut total_path = Vec::new();
                 let goal = current.clone();
                 let mut current = current;
@@ -54,38 +308,76 @@ mod a_star {
                     current = &prev.0;
                     total_path.push(prev.clone());
                 }
+                observer.on_finish(true);
                 return Ok((total_path, goal));
             }
 
+            if let Some(limit) = limits.check(expanded, started) {
+                observer.on_finish(false);
+                return Err(SearchError::Aborted(SearchAborted {
+                    limit,
+                    best: best.1,
+                    expanded,
+                }));
+            }
+
             let current = current.clone();
             open_set.remove(&current);
+            observer.on_expand(&current, open_set.len());
+            expanded += 1;
+            let current_h = heuristic(&current);
+            if let Some(h) = current_h
+                && h < best.0
+            {
+                best = (h, current.clone());
+            }
+            let g = g_score[&current];
 
             for (neighbor, edge) in get_neighbors(&current) {
-                let tentative_g_score = g_score
-                    .get(&current)
-                    .map(|s| *s + distance(&current, &edge, &neighbor))
-                    .unwrap_or(i64::MAX);
-                if tentative_g_score < g_score.get(&neighbor).copied().unwrap_or(i64::MAX) {
-                    came_from.insert(neighbor.clone(), (current.clone(), edge.clone()));
-                    g_score.insert(neighbor.clone(), tentative_g_score);
-                    let h = heuristic(&neighbor);
-                    let h = if h == i64::MAX {
-                        i64::MAX
-                    } else {
-                        tentative_g_score + h
-                    };
-                    f_score.insert(neighbor.clone(), h);
-                    open_set.insert(neighbor);
+                let d = distance(&current, &edge, &neighbor);
+                let tentative_g_score = g.plus(d);
+                if g_score
+                    .get(&neighbor)
+                    .is_some_and(|&s| s <= tentative_g_score)
+                {
+                    continue;
+                }
+                let Some(h) = heuristic(&neighbor) else {
+                    continue;
+                };
+                let f = tentative_g_score.plus(h);
+                if limits.exceeds_cost(f) {
+                    pruned = true;
+                    continue;
+                }
+                observer.on_relax(&current, &neighbor);
+                #[cfg(feature = "check-heuristic")]
+                if let Some(current_h) = current_h {
+                    check_consistent(current_h, d, h);
                 }
+                came_from.insert(neighbor.clone(), (current.clone(), edge.clone()));
+                g_score.insert(neighbor.clone(), tentative_g_score);
+                f_score.insert(neighbor.clone(), f);
+                open_set.insert(neighbor);
             }
         }
 
-        Err(NoPathFound)
+        observer.on_finish(false);
+        if pruned {
+            Err(SearchError::Aborted(SearchAborted {
+                limit: Limit::Cost,
+                best: best.1,
+                expanded,
+            }))
+        } else {
+            Err(SearchError::NoPathFound)
+        }
     }
 
     #[cfg(test)]
     mod tests {
         use super::*;
+        use crate::graph::{CancellationToken, SearchStats};
 
         #[test]
         fn test_a_star() {
@@ -119,12 +411,198 @@ mod a_star {
             let path: Vec<usize> = result.iter().rev().map(|(n, _)| *n).chain([goal]).collect();
             assert_eq!(path, vec![0, 5, 2]);
         }
+
+        #[test]
+        fn test_a_star_lexicographic() {
+            // (turns, steps): the long way without turning wins over the short one.
+            let edges: [&[(usize, (u32, u32))]; 4] = [
+                &[(1, (0, 5)), (2, (1, 1))],
+                &[(3, (0, 5))],
+                &[(3, (0, 1))],
+                &[],
+            ];
+            let path = |heuristic: &dyn Fn(&usize) -> Option<(u32, u32)>| {
+                let (path, _) = a_star_rev_pruned(
+                    &0,
+                    |&n| n == 3,
+                    |&n| edges[n].iter().copied(),
+                    heuristic,
+                    |_, &c, _| c,
+                )
+                .unwrap();
+                path.into_iter().map(|(n, _)| n).rev().collect::<Vec<_>>()
+            };
+            assert_eq!(path(&|_| Some((0, 0))), [0, 1]);
+            assert_eq!(path(&|&n| (n != 1).then_some((0, 0))), [0, 2]);
+            assert!(
+                a_star_rev_pruned(
+                    &0,
+                    |&n| n == 3,
+                    |&n| edges[n].to_vec(),
+                    |_| None,
+                    |_, &c, _| c
+                )
+                .is_err()
+            );
+        }
+
+        #[test]
+        fn test_a_star_max_heuristic() {
+            // odd nodes are dead ends, marked with the old `i64::MAX` sentinel.
+            let path = a_star(
+                &0,
+                |&n| n == 10,
+                |&n| {
+                    [(n + 1, ()), (n + 2, ())]
+                        .into_iter()
+                        .filter(move |_| n % 2 == 0)
+                },
+                |&n| if n % 2 == 1 { i64::MAX } else { (10 - n) / 2 },
+                |_, _, _| 1,
+            )
+            .unwrap();
+            assert_eq!(path.nodes(), [0, 2, 4, 6, 8, 10]);
+        }
+
+        #[test]
+        fn test_a_star_observed() {
+            let mut stats = SearchStats::new();
+            let result = a_star_rev_observed(
+                &0,
+                |&n| n == 10,
+                |&n| [(n + 1, ()), (n + 2, ())],
+                |&n| (10 - n) / 2,
+                |_, _, _| 1,
+                &mut stats,
+            );
+            assert_eq!(result.unwrap().0.len(), 5);
+            assert_eq!(stats.found, Some(true));
+            assert!((5..=10).contains(&stats.expanded));
+            assert!(stats.relaxed >= stats.expanded);
+            assert!(stats.max_frontier >= 1);
+        }
+
+        /// the last edge passed to [SearchObserver::on_relax].
+        #[cfg(feature = "check-heuristic")]
+        #[derive(Default)]
+        struct LastRelaxed(Option<(i32, i32)>);
+
+        #[cfg(feature = "check-heuristic")]
+        impl SearchObserver<i32> for LastRelaxed {
+            fn on_relax(&mut self, &from: &i32, &to: &i32) {
+                self.0 = Some((from, to));
+            }
+        }
+
+        /// a line 0 - 1 - ... - 9 where every step costs 2, searched from 0 to 9.
+        #[cfg(feature = "check-heuristic")]
+        fn line(heuristic: impl Fn(&i32) -> i32, observer: &mut LastRelaxed) -> i64 {
+            a_star_observed(
+                &0,
+                |&n| n == 9,
+                |&n| {
+                    [(n - 1, ()), (n + 1, ())]
+                        .into_iter()
+                        .filter(|n| (0..10).contains(&n.0))
+                },
+                heuristic,
+                |_, _, _| 2,
+                observer,
+            )
+            .unwrap()
+            .len() as i64
+        }
+
+        #[test]
+        #[cfg(feature = "check-heuristic")]
+        fn test_inconsistent_heuristic() {
+            let mut last = LastRelaxed::default();
+            assert_eq!(line(|&n| 2 * (9 - n), &mut last), 9);
+            assert_eq!(line(|_| 0, &mut last), 9);
+            // drops by 5 from 3 to 4, more than the step costs.
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                line(|&n| if n <= 3 { 15 } else { 2 * (9 - n) }, &mut last)
+            }));
+            assert!(result.is_err());
+            assert_eq!(last.0, Some((3, 4)));
+        }
+
+        #[test]
+        #[cfg(feature = "check-heuristic")]
+        #[should_panic(expected = "heuristic is not zero at the goal")]
+        fn test_nonzero_goal_heuristic() {
+            line(|&n| 9 - n + 1, &mut LastRelaxed::default());
+        }
+
+        #[test]
+        fn test_a_star_limited() {
+            let search = |limits: &SearchLimits| {
+                a_star_rev_limited(
+                    &0,
+                    |&n| n == 100,
+                    |&n| [(n + 1, ()), (n + 2, ())],
+                    |&n| Some((100 - n) / 2),
+                    |_, _, _| 1,
+                    limits,
+                    (),
+                )
+                .map(|(path, _)| path.len())
+            };
+            assert_eq!(search(&SearchLimits::default()), Ok(50));
+            let path = a_star_limited(
+                &0,
+                |&n| n == 100,
+                |&n| [(n + 1, ()), (n + 2, ())],
+                |&n| Some((100 - n) / 2),
+                |_, _, _| 1,
+                &SearchLimits::default(),
+                (),
+            )
+            .unwrap();
+            assert_eq!((path.cost(), path.start(), path.goal()), (50, &0, &100));
+            let limits = SearchLimits {
+                max_expanded: Some(10),
+                ..SearchLimits::default()
+            };
+            let Err(SearchError::Aborted(aborted)) = search(&limits) else {
+                panic!();
+            };
+            assert_eq!((aborted.limit, aborted.expanded), (Limit::Expanded, 10));
+            assert!(aborted.best > 0);
+            let limits = SearchLimits {
+                max_cost: Some(49),
+                ..SearchLimits::default()
+            };
+            assert!(matches!(
+                search(&limits),
+                Err(SearchError::Aborted(SearchAborted {
+                    limit: Limit::Cost,
+                    ..
+                }))
+            ));
+            let cancel = CancellationToken::new();
+            cancel.cancel();
+            let limits = SearchLimits {
+                cancel: Some(cancel),
+                ..SearchLimits::default()
+            };
+            assert!(matches!(
+                search(&limits),
+                Err(SearchError::Aborted(SearchAborted {
+                    limit: Limit::Cancelled,
+                    best: 0,
+                    expanded: 0,
+                }))
+            ));
+        }
     }
 }
 
 mod bfs_impl {
+    use super::{SearchAborted, SearchLimits, SearchObserver};
     use std::collections::{HashSet, VecDeque};
     use std::hash::Hash;
+    use std::time::Instant;
 
     trait INode: Clone + Hash + Eq {}
     impl<T: Clone + Hash + Eq> INode for T {}
@@ -134,11 +612,19 @@ mod bfs_impl {
         IsGoal: Fn(&Node) -> bool,
         Neighbors: IntoIterator<Item = Node>,
         GetNeighbors: Fn(&Node) -> Neighbors,
+        Observer: SearchObserver<Node>,
     > {
         explored_set: HashSet<Node>,
         frontier: VecDeque<Node>,
         is_goal: IsGoal,
         get_neighbors: GetNeighbors,
+        observer: Observer,
+        limits: SearchLimits,
+        started: Instant,
+        expanded: u64,
+        last_expanded: Option<Node>,
+        found: bool,
+        finished: bool,
     }
 
     impl<
@@ -146,16 +632,35 @@ mod bfs_impl {
         IsGoal: Fn(&Node) -> bool,
         Neighbors: IntoIterator<Item = Node>,
         GetNeighbors: Fn(&Node) -> Neighbors,
-    > BfsIter<Node, IsGoal, Neighbors, GetNeighbors>
+        Observer: SearchObserver<Node>,
+    > BfsIter<Node, IsGoal, Neighbors, GetNeighbors, Observer>
     {
-        fn new(start: Node, is_goal: IsGoal, get_neighbors: GetNeighbors) -> Self {
+        fn new(
+            start: Node,
+            is_goal: IsGoal,
+            get_neighbors: GetNeighbors,
+            limits: SearchLimits,
+            observer: Observer,
+        ) -> Self {
             Self {
                 explored_set: HashSet::from([start.clone()]),
                 frontier: VecDeque::from([start]),
                 is_goal,
                 get_neighbors,
+                observer,
+                limits,
+                started: Instant::now(),
+                expanded: 0,
+                last_expanded: None,
+                found: false,
+                finished: false,
             }
         }
+
+        fn finish(&mut self) {
+            self.finished = true;
+            self.observer.on_finish(self.found);
+        }
     }
 
     impl<
@@ -163,24 +668,42 @@ mod bfs_impl {
         IsGoal: Fn(&Node) -> bool,
         Neighbors: IntoIterator<Item = Node>,
         GetNeighbors: Fn(&Node) -> Neighbors,
-    > Iterator for BfsIter<Node, IsGoal, Neighbors, GetNeighbors>
+        Observer: SearchObserver<Node>,
+    > Iterator for BfsIter<Node, IsGoal, Neighbors, GetNeighbors, Observer>
     {
-        type Item = Node;
+        type Item = Result<Node, SearchAborted<Node>>;
 
         fn next(&mut self) -> Option<Self::Item> {
+            if self.finished {
+                return None;
+            }
             while let Some(current) = self.frontier.pop_front() {
                 if (self.is_goal)(&current) {
-                    return Some(current);
+                    self.found = true;
+                    return Some(Ok(current));
                 }
+                if let Some(limit) = self.limits.check(self.expanded, self.started) {
+                    self.finish();
+                    return Some(Err(SearchAborted {
+                        limit,
+                        best: self.last_expanded.take().unwrap_or(current),
+                        expanded: self.expanded,
+                    }));
+                }
+                self.observer.on_expand(&current, self.frontier.len());
+                self.expanded += 1;
                 for n in (self.get_neighbors)(&current) {
                     if self.explored_set.contains(&n) {
                         continue;
                     }
+                    self.observer.on_relax(&current, &n);
                     self.explored_set.insert(n.clone());
                     self.frontier.push_back(n);
                 }
+                self.last_expanded = Some(current);
             }
 
+            self.finish();
             None
         }
     }
@@ -196,41 +719,79 @@ mod bfs_impl {
         is_goal: IsGoal,
         get_neighbors: GetNeighbors,
     ) -> impl Iterator<Item = Node> {
-        BfsIter::new(start, is_goal, get_neighbors)
+        BfsIter::new(start, is_goal, get_neighbors, SearchLimits::default(), ())
+            .map_while(Result::ok)
     }
-}
 
-pub fn tsp(n: u16, dist: impl Fn(u16, u16) -> i32) -> i32 {
-    let mut g = HashMap::new();
-    for k in 0..n {
-        g.insert((1u64 << k, k), dist(0, k));
-    }
-
-    for s in 2..=n - 1 {
-        for sub in 0u64..(1u64 << (n - 1)) {
-            let sub = sub << 1;
-            if sub.count_ones() as u16 == s {
-                for k in 0..n {
-                    if ((1 << k) & sub) != 0 {
-                        let mut result = i32::MAX;
-                        for m in 0..n {
-                            if m != k && ((1 << m) & sub) != 0 {
-                                result = cmp::min(result, g[&(sub & !(1 << k), m)] + dist(m, k));
-                            }
-                        }
-                        g.insert((sub, k), result);
-                    }
-                }
-            }
-        }
+    /// [bfs], reporting its progress to `observer`. The search is finished once the iterator
+    /// returns `None`.
+    #[allow(private_bounds)]
+    pub fn bfs_observed<
+        Node: INode,
+        IsGoal: Fn(&Node) -> bool,
+        Neighbors: IntoIterator<Item = Node>,
+        GetNeighbors: Fn(&Node) -> Neighbors,
+        Observer: SearchObserver<Node>,
+    >(
+        start: Node,
+        is_goal: IsGoal,
+        get_neighbors: GetNeighbors,
+        observer: Observer,
+    ) -> impl Iterator<Item = Node> {
+        BfsIter::new(
+            start,
+            is_goal,
+            get_neighbors,
+            SearchLimits::default(),
+            observer,
+        )
+        .map_while(Result::ok)
     }
 
-    (1..n)
-        .map(|k| g[&(((1u64 << n) - 1) & !1u64, k)] + dist(k, 0))
-        .min()
-        .unwrap()
-}
+    /// [bfs_observed] that yields a single error and stops once one of the `limits` is reached.
+    #[allow(private_bounds)]
+    pub fn bfs_limited<
+        Node: INode,
+        IsGoal: Fn(&Node) -> bool,
+        Neighbors: IntoIterator<Item = Node>,
+        GetNeighbors: Fn(&Node) -> Neighbors,
+        Observer: SearchObserver<Node>,
+    >(
+        start: Node,
+        is_goal: IsGoal,
+        get_neighbors: GetNeighbors,
+        limits: SearchLimits,
+        observer: Observer,
+    ) -> impl Iterator<Item = Result<Node, SearchAborted<Node>>> {
+        BfsIter::new(start, is_goal, get_neighbors, limits, observer)
+    }
 
-pub fn inv_tsp(n: u16, dist: impl Fn(u16, u16) -> i32) -> i32 {
-    -tsp(n, |a, b| -dist(a, b))
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::graph::Limit;
+
+        #[test]
+        fn test_bfs_limited() {
+            let limits = SearchLimits {
+                max_expanded: Some(5),
+                ..SearchLimits::default()
+            };
+            let is_goal = |&n: &u32| n == 5 || n == 6;
+            let result: Vec<_> =
+                bfs_limited(0, is_goal, |&n| [2 * n + 1, 2 * n + 2], limits, ()).collect();
+            assert_eq!(
+                result,
+                [
+                    Ok(5),
+                    Ok(6),
+                    Err(SearchAborted {
+                        limit: Limit::Expanded,
+                        best: 4,
+                        expanded: 5,
+                    }),
+                ],
+            );
+        }
+    }
 }
@@ -0,0 +1,270 @@
+use super::{Cost, NoPathFound, Path, a_star_rev, bfs, tsp};
+use crate::grid::{Grid, Pos};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::hash::Hash;
+
+pub type NodeId = usize;
+
+/// Owned directed graph with dense node ids. `N` is the weight (or label) of a node, `E` the
+/// weight of an edge. Undirected graphs are stored with an edge in each direction.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Graph<N, E> {
+    pub nodes: Vec<N>,
+    /// `adjacency[a]` holds `(b, weight)` for every edge `a -> b`.
+    pub adjacency: Vec<Vec<(NodeId, E)>>,
+}
+
+impl<N, E> Default for Graph<N, E> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            adjacency: Vec::new(),
+        }
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.adjacency.iter().map(Vec::len).sum()
+    }
+
+    pub fn add_node(&mut self, node: N) -> NodeId {
+        self.nodes.push(node);
+        self.adjacency.push(Vec::new());
+        self.nodes.len() - 1
+    }
+
+    pub fn add_edge(&mut self, a: NodeId, b: NodeId, weight: E) {
+        assert!(b < self.len());
+        self.adjacency[a].push((b, weight));
+    }
+
+    pub fn add_undirected_edge(&mut self, a: NodeId, b: NodeId, weight: E)
+    where
+        E: Clone,
+    {
+        self.add_edge(a, b, weight.clone());
+        self.add_edge(b, a, weight);
+    }
+
+    pub fn node_ids(&self) -> impl Iterator<Item = NodeId> + use<N, E> {
+        0..self.len()
+    }
+
+    pub fn neighbors(&self, a: NodeId) -> impl Iterator<Item = (NodeId, &E)> {
+        self.adjacency[a].iter().map(|(b, e)| (*b, e))
+    }
+
+    /// weight of the first edge `a -> b`, if any.
+    pub fn edge(&self, a: NodeId, b: NodeId) -> Option<&E> {
+        self.adjacency[a]
+            .iter()
+            .find(|(n, _)| *n == b)
+            .map(|(_, e)| e)
+    }
+
+    /// Builds a graph from explicit node weights and `(from, to, weight)` triples.
+    pub fn from_edges(
+        nodes: impl IntoIterator<Item = N>,
+        edges: impl IntoIterator<Item = (NodeId, NodeId, E)>,
+    ) -> Self {
+        let nodes: Vec<_> = nodes.into_iter().collect();
+        let mut g = Self {
+            adjacency: nodes.iter().map(|_| Vec::new()).collect(),
+            nodes,
+        };
+        for (a, b, e) in edges {
+            g.add_edge(a, b, e);
+        }
+        g
+    }
+
+    /// Builds a graph from `(from, to, weight)` triples of node labels, assigning ids in order of
+    /// first appearance. Also returns the id of each label.
+    pub fn from_labeled_edges(
+        edges: impl IntoIterator<Item = (N, N, E)>,
+    ) -> (Self, HashMap<N, NodeId>)
+    where
+        N: Clone + Eq + Hash,
+    {
+        let mut g = Self::new();
+        let mut ids = HashMap::new();
+        let mut id_of = |g: &mut Self, n: N| match ids.entry(n) {
+            Entry::Occupied(e) => *e.get(),
+            Entry::Vacant(e) => {
+                let id = g.add_node(e.key().clone());
+                *e.insert(id)
+            }
+        };
+        for (a, b, e) in edges {
+            let a = id_of(&mut g, a);
+            let b = id_of(&mut g, b);
+            g.add_edge(a, b, e);
+        }
+        (g, ids)
+    }
+}
+
+impl<E> Graph<Pos, E> {
+    /// One node per passable cell, with edges between 4-connected passable neighbors weighted by
+    /// `cost(from, to)`. Also returns the id of each cell.
+    pub fn from_grid<Cell>(
+        grid: &Grid<Cell>,
+        is_passable: impl Fn(Pos, &Cell) -> bool,
+        cost: impl Fn(Pos, Pos) -> E,
+    ) -> (Self, Grid<Option<NodeId>>) {
+        let mut g = Self::new();
+        let mut ids = Grid::new_with(grid.size, || None);
+        for (p, c) in grid.iter_pos() {
+            if is_passable(p, c) {
+                ids[p] = Some(g.add_node(p));
+            }
+        }
+        for id in g.node_ids() {
+            let a @ [x, y] = g.nodes[id];
+            for b in [[x + 1, y], [x, y + 1], [x - 1, y], [x, y - 1]] {
+                if let Some(b_id) = grid.is_inside(b).then(|| ids[b]).flatten() {
+                    g.add_edge(id, b_id, cost(a, b));
+                }
+            }
+        }
+        (g, ids)
+    }
+}
+
+impl<N, E: Clone> Graph<N, E> {
+    /// [a_star](super::a_star) with `cost` of the edge weights as distances, for weights that are
+    /// not costs themselves, like a [Corridor](super::Corridor).
+    pub fn a_star_by<C: Cost>(
+        &self,
+        start: NodeId,
+        is_goal: impl Fn(NodeId) -> bool,
+        heuristic: impl Fn(NodeId) -> C,
+        cost: impl Fn(&E) -> C,
+    ) -> Result<Path<NodeId, E, C>, NoPathFound> {
+        let (rev, goal) = a_star_rev(
+            &start,
+            |&n| is_goal(n),
+            |&n| self.neighbors(n).map(|(b, e)| (b, e.clone())),
+            |&n| heuristic(n),
+            |_, e, _| cost(e),
+        )?;
+        Ok(Path::from_rev(rev, goal, |_, e, _| cost(e)))
+    }
+}
+
+impl<N, E: Cost> Graph<N, E> {
+    /// [a_star](super::a_star) using the edge weights as distances.
+    pub fn a_star(
+        &self,
+        start: NodeId,
+        is_goal: impl Fn(NodeId) -> bool,
+        heuristic: impl Fn(NodeId) -> E,
+    ) -> Result<Path<NodeId, E, E>, NoPathFound> {
+        self.a_star_by(start, is_goal, heuristic, |&e| e)
+    }
+
+    /// [a_star_rev] using the edge weights as distances.
+    pub fn a_star_rev(
+        &self,
+        start: NodeId,
+        is_goal: impl Fn(NodeId) -> bool,
+        heuristic: impl Fn(NodeId) -> E,
+    ) -> Result<(Vec<(NodeId, E)>, NodeId), NoPathFound> {
+        a_star_rev(
+            &start,
+            |&n| is_goal(n),
+            |&n| self.neighbors(n).map(|(b, e)| (b, *e)),
+            |&n| heuristic(n),
+            |_, &e, _| e,
+        )
+    }
+}
+
+impl<N, E: Copy + Into<i64>> Graph<N, E> {
+    /// [tsp] over all nodes, using the weight of the direct edge between two nodes as their
+    /// distance. Panics if the graph is not complete.
+    pub fn tsp(&self) -> (Vec<NodeId>, i64) {
+        let n = u16::try_from(self.len()).unwrap();
+        let route = tsp(n, |a, b| {
+            let (a, b) = (a as NodeId, b as NodeId);
+            match self.edge(a, b) {
+                Some(&e) => e.into(),
+                None if a == b => 0,
+                None => panic!("no edge {a} -> {b}"),
+            }
+        });
+        (
+            route.order.into_iter().map(NodeId::from).collect(),
+            route.cost,
+        )
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    /// [bfs] over the edges, ignoring their weights.
+    pub fn bfs(
+        &self,
+        start: NodeId,
+        is_goal: impl Fn(NodeId) -> bool,
+    ) -> impl Iterator<Item = NodeId> {
+        bfs(
+            start,
+            move |&n| is_goal(n),
+            |&n| self.neighbors(n).map(|(b, _)| b),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_grid() {
+        let grid = Grid::from_lines(["..#", "#..", "..."], |_, c| c == '#');
+        let (g, ids) = Graph::from_grid(&grid, |_, &wall| !wall, |_, _| 1i64);
+        assert_eq!(g.len(), 7);
+        assert_eq!(g.edge_count(), 2 * 7);
+        let start = ids[[0, 0]].unwrap();
+        let goal = ids[[0, 2]].unwrap();
+        let path = g.a_star(start, |n| n == goal, |_| 0).unwrap();
+        let nodes: Vec<_> = path.nodes().iter().map(|&n| g.nodes[n]).collect();
+        assert_eq!(nodes, [[0, 0], [1, 0], [1, 1], [1, 2], [0, 2]]);
+        assert_eq!(path.cost(), 4);
+        assert_eq!(g.bfs(start, |n| n == goal).collect::<Vec<_>>(), [goal]);
+    }
+
+    #[test]
+    fn test_from_labeled_edges() {
+        let (g, ids) = Graph::from_labeled_edges(
+            [
+                ("a", "b", 1),
+                ("b", "c", 2),
+                ("c", "d", 3),
+                ("d", "a", 4),
+                ("a", "c", 5),
+                ("b", "d", 6),
+            ]
+            .into_iter()
+            .flat_map(|(a, b, e)| [(a, b, e), (b, a, e)]),
+        );
+        assert_eq!(ids.len(), 4);
+        assert_eq!(g.nodes[ids["c"]], "c");
+        assert_eq!(g.edge(ids["b"], ids["d"]), Some(&6));
+        assert_eq!(g.tsp(), (vec![0, 3, 2, 1], 10));
+    }
+}
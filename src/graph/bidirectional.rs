@@ -0,0 +1,266 @@
+use super::{Cost, NoPathFound, Path};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+/// joins the forward search tree, which leads from the meeting point back to the start, and the
+/// backward one, which leads from it to the goal, into a path in reverse order.
+fn stitch<K: Clone, Node, Edge>(
+    meeting: K,
+    prev: impl Fn(&K) -> Option<(K, Edge)>,
+    next: impl Fn(&K) -> Option<(K, Edge)>,
+    node: impl Fn(&K) -> Node,
+) -> (Vec<(Node, Edge)>, Node) {
+    let mut forward = Vec::new();
+    let mut k = meeting.clone();
+    while let Some((n, e)) = next(&k) {
+        forward.push((node(&k), e));
+        k = n;
+    }
+    let goal = node(&k);
+    let mut rev: Vec<_> = forward.into_iter().rev().collect();
+    let mut k = meeting;
+    while let Some((p, e)) = prev(&k) {
+        rev.push((node(&p), e));
+        k = p;
+    }
+    (rev, goal)
+}
+
+/// nodes reached from one end, with their distance and the step towards that end.
+type Tree<Node, Edge> = HashMap<Node, (usize, Option<(Node, Edge)>)>;
+
+/// BFS from both `start` and `goal` at once, meeting in the middle. `get_predecessors` yields the
+/// nodes with an edge to the given node, together with that edge. Always expands the smaller
+/// frontier by a whole layer, so the path found is a shortest one; its cost is the number of
+/// steps.
+pub fn bidirectional_bfs<Node, Edge, Neighbors, Predecessors>(
+    start: &Node,
+    goal: &Node,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    get_predecessors: impl Fn(&Node) -> Predecessors,
+) -> Result<Path<Node, Edge>, NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    Predecessors: IntoIterator<Item = (Node, Edge)>,
+{
+    let mut forward: Tree<Node, Edge> = HashMap::from([(start.clone(), (0, None))]);
+    let mut backward: Tree<Node, Edge> = HashMap::from([(goal.clone(), (0, None))]);
+    let mut forward_frontier = vec![start.clone()];
+    let mut backward_frontier = vec![goal.clone()];
+    let mut meeting = (start == goal).then(|| start.clone());
+
+    while meeting.is_none() && !forward_frontier.is_empty() && !backward_frontier.is_empty() {
+        let is_forward = forward_frontier.len() <= backward_frontier.len();
+        let (this, other, frontier) = if is_forward {
+            (&mut forward, &backward, &mut forward_frontier)
+        } else {
+            (&mut backward, &forward, &mut backward_frontier)
+        };
+        let mut best: Option<(usize, Node)> = None;
+        let mut next_frontier = Vec::new();
+        for u in frontier.drain(..) {
+            let d = this[&u].0 + 1;
+            let steps: Vec<_> = if is_forward {
+                get_neighbors(&u).into_iter().collect()
+            } else {
+                get_predecessors(&u).into_iter().collect()
+            };
+            for (v, e) in steps {
+                if this.contains_key(&v) {
+                    continue;
+                }
+                this.insert(v.clone(), (d, Some((u.clone(), e))));
+                if let Some(&(o, _)) = other.get(&v)
+                    && best.as_ref().is_none_or(|(b, _)| d + o < *b)
+                {
+                    best = Some((d + o, v.clone()));
+                }
+                next_frontier.push(v);
+            }
+        }
+        *frontier = next_frontier;
+        meeting = best.map(|(_, m)| m);
+    }
+
+    let meeting = meeting.ok_or(NoPathFound)?;
+    let (rev, goal) = stitch(
+        meeting,
+        |n| forward[n].1.clone(),
+        |n| backward[n].1.clone(),
+        Node::clone,
+    );
+    Ok(Path::from_rev(rev, goal, |_, _, _| 1))
+}
+
+struct Side<C, Edge> {
+    dist: HashMap<usize, C>,
+    /// the step towards this side's end.
+    parent: HashMap<usize, (usize, Edge)>,
+    queue: BinaryHeap<Reverse<(C, usize)>>,
+}
+
+impl<C: Cost, Edge> Side<C, Edge> {
+    fn new(id: usize) -> Self {
+        Self {
+            dist: HashMap::from([(id, C::zero())]),
+            parent: HashMap::new(),
+            queue: BinaryHeap::from([Reverse((C::zero(), id))]),
+        }
+    }
+
+    /// the lowest distance in the queue, dropping outdated entries.
+    fn peek(&mut self) -> Option<C> {
+        while let Some(&Reverse((d, id))) = self.queue.peek() {
+            if d == self.dist[&id] {
+                return Some(d);
+            }
+            self.queue.pop();
+        }
+        None
+    }
+}
+
+/// Dijkstra from both `start` and `goal` at once, with `get_predecessors` as in
+/// [bidirectional_bfs]. `distance(a, edge, b)` is always called for an edge from `a` to `b`,
+/// whichever side relaxes it. Stops once the two queues together can't beat the best meeting
+/// point, so costs must not be negative.
+pub fn bidirectional_dijkstra<Node, Edge, Neighbors, Predecessors, C>(
+    start: &Node,
+    goal: &Node,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    get_predecessors: impl Fn(&Node) -> Predecessors,
+    distance: impl Fn(&Node, &Edge, &Node) -> C,
+) -> Result<Path<Node, Edge, C>, NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    Predecessors: IntoIterator<Item = (Node, Edge)>,
+    C: Cost,
+{
+    let mut ids = HashMap::new();
+    let mut nodes = Vec::new();
+    let mut intern = |n: Node, nodes: &mut Vec<Node>| {
+        *ids.entry(n).or_insert_with_key(|n| {
+            nodes.push(n.clone());
+            nodes.len() - 1
+        })
+    };
+    let s = intern(start.clone(), &mut nodes);
+    let g = intern(goal.clone(), &mut nodes);
+    let mut forward = Side::<C, Edge>::new(s);
+    let mut backward = Side::<C, Edge>::new(g);
+    let mut best: Option<(C, usize)> = (s == g).then_some((C::zero(), s));
+
+    while let (Some(f), Some(b)) = (forward.peek(), backward.peek()) {
+        if best.is_some_and(|(mu, _)| f.plus(b) >= mu) {
+            break;
+        }
+        let is_forward = f <= b;
+        let (this, other) = if is_forward {
+            (&mut forward, &backward)
+        } else {
+            (&mut backward, &forward)
+        };
+        let Reverse((d, u)) = this.queue.pop().unwrap();
+        let node = nodes[u].clone();
+        let steps: Vec<_> = if is_forward {
+            get_neighbors(&node).into_iter().collect()
+        } else {
+            get_predecessors(&node).into_iter().collect()
+        };
+        for (v_node, e) in steps {
+            let w = if is_forward {
+                distance(&node, &e, &v_node)
+            } else {
+                distance(&v_node, &e, &node)
+            };
+            let v = intern(v_node, &mut nodes);
+            let nd = d.plus(w);
+            if this.dist.get(&v).is_some_and(|&o| o <= nd) {
+                continue;
+            }
+            this.dist.insert(v, nd);
+            this.parent.insert(v, (u, e));
+            this.queue.push(Reverse((nd, v)));
+            if let Some(&o) = other.dist.get(&v)
+                && best.is_none_or(|(mu, _)| nd.plus(o) < mu)
+            {
+                best = Some((nd.plus(o), v));
+            }
+        }
+    }
+
+    let (_, meeting) = best.ok_or(NoPathFound)?;
+    let (rev, goal) = stitch(
+        meeting,
+        |k| forward.parent.get(k).cloned(),
+        |k| backward.parent.get(k).cloned(),
+        |&k| nodes[k].clone(),
+    );
+    Ok(Path::from_rev(rev, goal, distance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::a_star;
+
+    /// edges `(a, b, cost)` of a small directed graph with two routes of different length.
+    const EDGES: [(u32, u32, i64); 9] = [
+        (0, 1, 1),
+        (1, 2, 1),
+        (2, 3, 1),
+        (3, 4, 1),
+        (4, 9, 1),
+        (0, 5, 4),
+        (5, 6, 4),
+        (6, 9, 4),
+        (9, 0, 1),
+    ];
+
+    fn neighbors(n: &u32) -> Vec<(u32, i64)> {
+        EDGES
+            .iter()
+            .filter(|e| e.0 == *n)
+            .map(|e| (e.1, e.2))
+            .collect()
+    }
+
+    fn predecessors(n: &u32) -> Vec<(u32, i64)> {
+        EDGES
+            .iter()
+            .filter(|e| e.1 == *n)
+            .map(|e| (e.0, e.2))
+            .collect()
+    }
+
+    #[test]
+    fn test_bidirectional_bfs() {
+        let path = bidirectional_bfs(&0, &9, neighbors, predecessors).unwrap();
+        assert_eq!(path.nodes(), [0, 5, 6, 9]);
+        assert_eq!(path.cost(), 3);
+        assert_eq!(path.edges(), [4, 4, 4]);
+        let path = bidirectional_bfs(&2, &2, neighbors, predecessors).unwrap();
+        assert_eq!(path.nodes(), [2]);
+        assert!(bidirectional_bfs(&0, &7, neighbors, predecessors).is_err());
+    }
+
+    #[test]
+    fn test_bidirectional_dijkstra() {
+        let distance = |_: &u32, &c: &i64, _: &u32| c;
+        let path = bidirectional_dijkstra(&0, &9, neighbors, predecessors, distance).unwrap();
+        assert_eq!(path.nodes(), [0, 1, 2, 3, 4, 9]);
+        assert_eq!(path.costs(), [0, 1, 2, 3, 4, 5]);
+        for (a, b) in [(5, 4), (6, 1), (9, 9), (3, 2)] {
+            let path = bidirectional_dijkstra(&a, &b, neighbors, predecessors, distance).unwrap();
+            let expected = a_star(&a, |&n| n == b, neighbors, |_| 0, distance).unwrap();
+            assert_eq!(path.cost(), expected.cost());
+            assert_eq!((path.start(), path.goal()), (&a, &b));
+        }
+        assert!(bidirectional_dijkstra(&0, &7, neighbors, predecessors, distance).is_err());
+    }
+}
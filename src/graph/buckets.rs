@@ -0,0 +1,319 @@
+use super::{NoPathFound, Path};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Which priority queue [bucket_search_rev] uses.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum BucketQueue {
+    /// [ZeroOne](Self::ZeroOne) while every edge costs 0 or 1, switching to Dial's algorithm
+    /// with as many buckets as needed at the first more expensive edge. Works for any
+    /// non-negative integer costs.
+    #[default]
+    Auto,
+    /// 0-1 BFS: a deque, only for edges that cost 0 or 1.
+    ZeroOne,
+    /// Dial's algorithm: buckets for edges that cost at most the given amount.
+    Dial(usize),
+}
+
+trait Queue<T> {
+    fn push(&mut self, cost: i64, item: T);
+    fn pop(&mut self) -> Option<(i64, T)>;
+}
+
+struct ZeroOneQueue<T> {
+    current: i64,
+    deque: VecDeque<(i64, T)>,
+}
+
+impl<T> Queue<T> for ZeroOneQueue<T> {
+    fn push(&mut self, cost: i64, item: T) {
+        if cost == self.current {
+            self.deque.push_front((cost, item));
+        } else {
+            assert_eq!(cost, self.current + 1, "edge costs must be 0 or 1");
+            self.deque.push_back((cost, item));
+        }
+    }
+
+    fn pop(&mut self) -> Option<(i64, T)> {
+        let (cost, item) = self.deque.pop_front()?;
+        self.current = cost;
+        Some((cost, item))
+    }
+}
+
+/// Buckets for the costs `base..base + buckets.len()`.
+struct Buckets<T> {
+    base: i64,
+    max_edge: Option<i64>,
+    buckets: VecDeque<Vec<T>>,
+}
+
+impl<T> Queue<T> for Buckets<T> {
+    fn push(&mut self, cost: i64, item: T) {
+        let i = (cost - self.base) as usize;
+        if let Some(max) = self.max_edge {
+            assert!(i as i64 <= max, "edge costs must be at most {}", max);
+        }
+        if i >= self.buckets.len() {
+            self.buckets.resize_with(i + 1, Vec::new);
+        }
+        self.buckets[i].push(item);
+    }
+
+    fn pop(&mut self) -> Option<(i64, T)> {
+        loop {
+            let front = self.buckets.front_mut()?;
+            if let Some(item) = front.pop() {
+                return Some((self.base, item));
+            }
+            self.buckets.pop_front();
+            self.base += 1;
+        }
+    }
+}
+
+/// the queue for [BucketQueue::Auto].
+enum AutoQueue<T> {
+    ZeroOne(ZeroOneQueue<T>),
+    Buckets(Buckets<T>),
+}
+
+impl<T> Queue<T> for AutoQueue<T> {
+    fn push(&mut self, cost: i64, item: T) {
+        if let AutoQueue::ZeroOne(q) = self
+            && cost > q.current + 1
+        {
+            let mut buckets = Buckets {
+                base: q.current,
+                max_edge: None,
+                buckets: VecDeque::new(),
+            };
+            for (c, t) in q.deque.drain(..) {
+                buckets.push(c, t);
+            }
+            *self = AutoQueue::Buckets(buckets);
+        }
+        match self {
+            AutoQueue::ZeroOne(q) => q.push(cost, item),
+            AutoQueue::Buckets(b) => b.push(cost, item),
+        }
+    }
+
+    fn pop(&mut self) -> Option<(i64, T)> {
+        match self {
+            AutoQueue::ZeroOne(q) => q.pop(),
+            AutoQueue::Buckets(b) => b.pop(),
+        }
+    }
+}
+
+fn search_rev<Node, Edge, Neighbors>(
+    mut queue: impl Queue<Node>,
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    distance: impl Fn(&Node, &Edge, &Node) -> i64,
+) -> Result<(Vec<(Node, Edge)>, Node), NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+{
+    let mut came_from = HashMap::<Node, (Node, Edge)>::new();
+    let mut g_score = HashMap::from([(start.clone(), 0i64)]);
+    queue.push(0, start.clone());
+
+    while let Some((cost, current)) = queue.pop() {
+        if cost > g_score[&current] {
+            continue;
+        }
+        if is_goal(&current) {
+            let mut total_path = Vec::new();
+            let mut node = &current;
+            while let Some(prev) = came_from.get(node) {
+                total_path.push(prev.clone());
+                node = &prev.0;
+            }
+            return Ok((total_path, current));
+        }
+        for (neighbor, edge) in get_neighbors(&current) {
+            let d = distance(&current, &edge, &neighbor);
+            assert!(d >= 0, "edge costs must not be negative");
+            let tentative = cost + d;
+            match g_score.entry(neighbor.clone()) {
+                Entry::Occupied(e) if *e.get() <= tentative => continue,
+                Entry::Occupied(mut e) => {
+                    e.insert(tentative);
+                }
+                Entry::Vacant(e) => {
+                    e.insert(tentative);
+                }
+            }
+            came_from.insert(neighbor.clone(), (current.clone(), edge));
+            queue.push(tentative, neighbor);
+        }
+    }
+
+    Err(NoPathFound)
+}
+
+/// Dijkstra for small non-negative integer edge costs, using a bucket queue instead of a heap, so
+/// it runs in linear time in the number of edges plus the path cost. Same arguments and result as
+/// [a_star](super::a_star) without a heuristic.
+pub fn bucket_search<Node, Edge, Neighbors>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    distance: impl Fn(&Node, &Edge, &Node) -> i64,
+    queue: BucketQueue,
+) -> Result<Path<Node, Edge>, NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+{
+    let (rev, goal) = bucket_search_rev(start, is_goal, get_neighbors, &distance, queue)?;
+    Ok(Path::from_rev(rev, goal, distance))
+}
+
+/// [bucket_search] with the path in reverse order, like [a_star_rev](super::a_star_rev).
+pub fn bucket_search_rev<Node, Edge, Neighbors>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    distance: impl Fn(&Node, &Edge, &Node) -> i64,
+    queue: BucketQueue,
+) -> Result<(Vec<(Node, Edge)>, Node), NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+{
+    match queue {
+        BucketQueue::ZeroOne => search_rev(
+            ZeroOneQueue {
+                current: 0,
+                deque: VecDeque::new(),
+            },
+            start,
+            is_goal,
+            get_neighbors,
+            distance,
+        ),
+        BucketQueue::Auto => search_rev(
+            AutoQueue::ZeroOne(ZeroOneQueue {
+                current: 0,
+                deque: VecDeque::new(),
+            }),
+            start,
+            is_goal,
+            get_neighbors,
+            distance,
+        ),
+        BucketQueue::Dial(max_edge) => search_rev(
+            Buckets {
+                base: 0,
+                max_edge: Some(max_edge as i64),
+                buckets: VecDeque::with_capacity(max_edge + 1),
+            },
+            start,
+            is_goal,
+            get_neighbors,
+            distance,
+        ),
+    }
+}
+
+/// [bucket_search] with [BucketQueue::ZeroOne].
+pub fn zero_one_bfs<Node, Edge, Neighbors>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    distance: impl Fn(&Node, &Edge, &Node) -> i64,
+) -> Result<Path<Node, Edge>, NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+{
+    bucket_search(
+        start,
+        is_goal,
+        get_neighbors,
+        distance,
+        BucketQueue::ZeroOne,
+    )
+}
+
+/// [bucket_search_rev] with [BucketQueue::ZeroOne].
+pub fn zero_one_bfs_rev<Node, Edge, Neighbors>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    distance: impl Fn(&Node, &Edge, &Node) -> i64,
+) -> Result<(Vec<(Node, Edge)>, Node), NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+{
+    bucket_search_rev(
+        start,
+        is_goal,
+        get_neighbors,
+        distance,
+        BucketQueue::ZeroOne,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::a_star;
+
+    type Pos = [usize; 2];
+
+    /// moving onto a cell costs its digit, returns the cost of the path found from the top left
+    /// to the bottom right corner.
+    fn path_cost(grid: [&str; 4], queue: Option<BucketQueue>) -> i64 {
+        let grid = grid.map(str::as_bytes);
+        let neighbors = |&[x, y]: &Pos| {
+            [
+                [x + 1, y],
+                [x, y + 1],
+                [x.wrapping_sub(1), y],
+                [x, y.wrapping_sub(1)],
+            ]
+            .into_iter()
+            .filter(|&[x, y]| x < 4 && y < 4)
+            .map(|n| (n, ()))
+        };
+        let cost = |_: &Pos, _: &(), &[x, y]: &Pos| (grid[y][x] - b'0') as i64;
+        let is_goal = |&n: &Pos| n == [3, 3];
+        match queue {
+            Some(queue) => bucket_search(&[0, 0], is_goal, neighbors, cost, queue),
+            None => a_star(&[0, 0], is_goal, neighbors, |_| 0, cost),
+        }
+        .unwrap()
+        .cost()
+    }
+
+    #[test]
+    fn test_bucket_search() {
+        let grid = ["0121", "1200", "2102", "0210"];
+        assert_eq!(path_cost(grid, None), 4);
+        assert_eq!(path_cost(grid, Some(BucketQueue::Auto)), 4);
+        assert_eq!(path_cost(grid, Some(BucketQueue::Dial(2))), 4);
+        let grid = ["0111", "1100", "1101", "0110"];
+        assert_eq!(path_cost(grid, None), 3);
+        assert_eq!(path_cost(grid, Some(BucketQueue::ZeroOne)), 3);
+        assert_eq!(path_cost(grid, Some(BucketQueue::Auto)), 3);
+        // only 0 and 1 until the last step, which forces the switch to buckets.
+        let grid = ["0111", "1100", "1101", "0119"];
+        assert_eq!(path_cost(grid, None), 12);
+        assert_eq!(path_cost(grid, Some(BucketQueue::Auto)), 12);
+    }
+}
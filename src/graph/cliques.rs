@@ -0,0 +1,171 @@
+use std::collections::BTreeSet;
+
+/// Undirected graph for clique searches. Node ids follow the order of the nodes, so lists of ids
+/// sorted by id are sorted by node as well.
+#[derive(Clone, Debug)]
+pub struct CliqueGraph<N> {
+    pub nodes: Vec<N>,
+    neighbors: Vec<BTreeSet<usize>>,
+}
+
+impl<N: Ord + Clone> CliqueGraph<N> {
+    /// Builds the graph from undirected edges. Self loops and duplicate edges are ignored.
+    pub fn from_edges(edges: impl IntoIterator<Item = (N, N)>) -> Self {
+        let edges: Vec<_> = edges.into_iter().collect();
+        let nodes: Vec<N> = edges
+            .iter()
+            .flat_map(|(a, b)| [a.clone(), b.clone()])
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let id = |n: &N| nodes.binary_search(n).unwrap();
+        let mut neighbors = vec![BTreeSet::new(); nodes.len()];
+        for (a, b) in &edges {
+            let (a, b) = (id(a), id(b));
+            if a != b {
+                neighbors[a].insert(b);
+                neighbors[b].insert(a);
+            }
+        }
+        Self { nodes, neighbors }
+    }
+
+    fn to_nodes(&self, ids: &[usize]) -> Vec<N> {
+        ids.iter().map(|&i| self.nodes[i].clone()).collect()
+    }
+
+    /// Every triangle with at least one node for which `pred` holds, each sorted, in sorted order.
+    pub fn triangles_with(&self, pred: impl Fn(&N) -> bool) -> Vec<[N; 3]> {
+        let mut triangles = Vec::new();
+        for a in 0..self.nodes.len() {
+            for &b in self.neighbors[a].range(a + 1..) {
+                for &c in self.neighbors[b].range(b + 1..) {
+                    if self.neighbors[a].contains(&c)
+                        && [a, b, c].iter().any(|&i| pred(&self.nodes[i]))
+                    {
+                        triangles.push([a, b, c].map(|i| self.nodes[i].clone()));
+                    }
+                }
+            }
+        }
+        triangles
+    }
+
+    /// Bron–Kerbosch with pivoting: every maximal clique, each sorted, in sorted order.
+    pub fn maximal_cliques(&self) -> Vec<Vec<N>> {
+        if self.nodes.is_empty() {
+            return Vec::new();
+        }
+        let mut cliques = Vec::new();
+        self.bron_kerbosch(
+            &mut Vec::new(),
+            (0..self.nodes.len()).collect(),
+            BTreeSet::new(),
+            &mut |r| {
+                let mut r = r.to_vec();
+                r.sort_unstable();
+                cliques.push(r);
+            },
+        );
+        cliques.sort_unstable();
+        cliques.iter().map(|c| self.to_nodes(c)).collect()
+    }
+
+    /// The largest clique, sorted. Of several of the same size, the first in sorted order.
+    pub fn maximum_clique(&self) -> Vec<N> {
+        let cliques = self.maximal_cliques();
+        let max = cliques.iter().map(Vec::len).max().unwrap_or(0);
+        cliques
+            .into_iter()
+            .find(|c| c.len() == max)
+            .unwrap_or_default()
+    }
+
+    /// reports every maximal clique that contains `r`, extended by nodes of `p` but none of `x`.
+    fn bron_kerbosch(
+        &self,
+        r: &mut Vec<usize>,
+        mut p: BTreeSet<usize>,
+        mut x: BTreeSet<usize>,
+        report: &mut impl FnMut(&[usize]),
+    ) {
+        let Some(pivot) = p
+            .iter()
+            .chain(&x)
+            .max_by_key(|&&u| p.intersection(&self.neighbors[u]).count())
+            .copied()
+        else {
+            report(r);
+            return;
+        };
+        let candidates: Vec<_> = p.difference(&self.neighbors[pivot]).copied().collect();
+        for v in candidates {
+            let n = &self.neighbors[v];
+            r.push(v);
+            self.bron_kerbosch(
+                r,
+                p.intersection(n).copied().collect(),
+                x.intersection(n).copied().collect(),
+                report,
+            );
+            r.pop();
+            p.remove(&v);
+            x.insert(v);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EDGES: &str = "kh-tc qp-kh de-cg ka-co yn-aq qp-ub cg-tb vc-aq tb-ka wh-tc yn-cg kh-ub \
+        ta-co de-co tc-td tb-wq wh-td ta-ka td-qp aq-cg wq-ub ub-vc de-ta wq-aq wq-vc wh-yn ka-de \
+        kh-ta co-tc wh-qp tb-vc td-yn";
+
+    fn graph() -> CliqueGraph<&'static str> {
+        CliqueGraph::from_edges(EDGES.split_whitespace().map(|e| e.split_once('-').unwrap()))
+    }
+
+    #[test]
+    fn test_triangles() {
+        let g = graph();
+        assert_eq!(g.triangles_with(|_| true).len(), 12);
+        assert_eq!(
+            g.triangles_with(|n| n.starts_with('t')),
+            [
+                ["co", "de", "ta"],
+                ["co", "ka", "ta"],
+                ["de", "ka", "ta"],
+                ["qp", "td", "wh"],
+                ["tb", "vc", "wq"],
+                ["tc", "td", "wh"],
+                ["td", "wh", "yn"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cliques() {
+        let g = graph();
+        assert_eq!(g.maximum_clique(), ["co", "de", "ka", "ta"]);
+        let cliques = g.maximal_cliques();
+        assert!(cliques.is_sorted());
+        for c in &cliques {
+            assert!(c.is_sorted());
+            // maximal: no other node is adjacent to all of them.
+            let ids: Vec<_> = c
+                .iter()
+                .map(|n| g.nodes.binary_search(n).unwrap())
+                .collect();
+            assert!(
+                (0..g.nodes.len())
+                    .all(|v| ids.contains(&v) || !ids.iter().all(|&u| g.neighbors[u].contains(&v)))
+            );
+        }
+
+        let empty = CliqueGraph::<&str>::from_edges([]);
+        assert!(empty.maximal_cliques().is_empty());
+        assert!(empty.maximum_clique().is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+use super::{Graph, NodeId};
+use crate::grid::{Grid, Pos};
+use std::collections::{HashMap, HashSet};
+
+/// Edge of a contracted maze: the cells walked from one junction to the next.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Corridor {
+    /// number of steps, i.e. `cells.len() - 1`.
+    pub length: i64,
+    /// every cell from the source node to the target node, both included.
+    pub cells: Vec<Pos>,
+}
+
+fn open_neighbors<Cell>(
+    grid: &Grid<Cell>,
+    is_passable: &impl Fn(Pos, &Cell) -> bool,
+    [x, y]: Pos,
+) -> impl Iterator<Item = Pos> {
+    [[x + 1, y], [x, y + 1], [x - 1, y], [x, y - 1]]
+        .into_iter()
+        .filter(move |&n| grid.is_inside(n) && is_passable(n, &grid[n]))
+}
+
+/// Compresses a 4-connected maze into a graph whose nodes are junctions, dead-ends and the
+/// positions in `keep` (start, goal, ...), connected by the [Corridors](Corridor) between them.
+/// Every corridor is stored in both directions. Corridors leading back to the node they started
+/// from, and cycles without any node on them, are dropped since they are never part of a
+/// shortest path.
+///
+/// Also returns the id of every node by position.
+pub fn contract_corridors<Cell>(
+    grid: &Grid<Cell>,
+    is_passable: impl Fn(Pos, &Cell) -> bool,
+    keep: impl IntoIterator<Item = Pos>,
+) -> (Graph<Pos, Corridor>, HashMap<Pos, NodeId>) {
+    let keep: HashSet<Pos> = keep.into_iter().collect();
+    let mut g = Graph::new();
+    let mut ids = HashMap::new();
+    for (p, c) in grid.iter_pos() {
+        if is_passable(p, c)
+            && (keep.contains(&p) || open_neighbors(grid, &is_passable, p).count() != 2)
+        {
+            ids.insert(p, g.add_node(p));
+        }
+    }
+
+    for a in g.node_ids() {
+        let start = g.nodes[a];
+        for first in open_neighbors(grid, &is_passable, start) {
+            let mut cells = vec![start, first];
+            while !ids.contains_key(cells.last().unwrap()) {
+                let &[.., prev, current] = cells.as_slice() else {
+                    unreachable!()
+                };
+                let next = open_neighbors(grid, &is_passable, current)
+                    .find(|&n| n != prev)
+                    .unwrap();
+                cells.push(next);
+            }
+            let b = ids[cells.last().unwrap()];
+            if a != b {
+                g.add_edge(
+                    a,
+                    b,
+                    Corridor {
+                        length: cells.len() as i64 - 1,
+                        cells,
+                    },
+                );
+            }
+        }
+    }
+
+    (g, ids)
+}
+
+/// Turns consecutive corridors of a path through a contracted maze back into the cells walked.
+pub fn expand_corridors<'c>(corridors: impl IntoIterator<Item = &'c Corridor>) -> Vec<Pos> {
+    let mut cells = Vec::new();
+    for c in corridors {
+        if let Some(&last) = cells.last() {
+            debug_assert_eq!(last, c.cells[0]);
+            cells.extend_from_slice(&c.cells[1..]);
+        } else {
+            cells.extend_from_slice(&c.cells);
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contract_corridors() {
+        let grid = Grid::from_lines(
+            [
+                "#########",
+                "#S....#.#",
+                "#.###.#.#",
+                "#.#...#.#",
+                "#.#.###.#",
+                "#.......#",
+                "#.###.###",
+                "#....E..#",
+                "#########",
+            ],
+            |_, c| c,
+        );
+        let (start, goal) = ([1, 1], [5, 7]);
+        let (g, ids) = contract_corridors(&grid, |_, &c| c != '#', [start, goal]);
+        assert_eq!(g.len(), 7);
+        let path = g
+            .a_star_by(ids[&start], |n| n == ids[&goal], |_| 0, |c| c.length)
+            .unwrap();
+        let cells = expand_corridors(path.edges());
+        assert_eq!(cells.len(), 11);
+        assert_eq!(cells.first(), Some(&start));
+        assert_eq!(cells.last(), Some(&goal));
+    }
+}
@@ -0,0 +1,105 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Path costs for the searches: totally ordered, with a zero and addition. Implemented for the
+/// primitive integers, [OrdF64] and tuples of costs, which are compared lexicographically, so
+/// `(turns, steps)` prefers fewer turns and only then fewer steps.
+pub trait Cost: Copy + Ord {
+    fn zero() -> Self;
+
+    /// Integers saturate, so that a heuristic of `MAX` still sorts a node last instead of
+    /// overflowing. To never queue such nodes at all, use [a_star_pruned](super::a_star_pruned).
+    fn plus(self, other: Self) -> Self;
+}
+
+macro_rules! impl_cost_for_int {
+    ($($t:ty),*) => {
+        $(impl Cost for $t {
+            fn zero() -> Self {
+                0
+            }
+
+            fn plus(self, other: Self) -> Self {
+                self.saturating_add(other)
+            }
+        })*
+    };
+}
+
+impl_cost_for_int!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+macro_rules! impl_cost_for_tuple {
+    ($($t:ident $i:tt),*) => {
+        impl<$($t: Cost),*> Cost for ($($t,)*) {
+            fn zero() -> Self {
+                ($($t::zero(),)*)
+            }
+
+            fn plus(self, other: Self) -> Self {
+                ($(self.$i.plus(other.$i),)*)
+            }
+        }
+    };
+}
+
+impl_cost_for_tuple!(A 0, B 1);
+impl_cost_for_tuple!(A 0, B 1, C 2);
+impl_cost_for_tuple!(A 0, B 1, C 2, D 3);
+
+/// `f64` ordered by [f64::total_cmp], so it can be used as a [Cost].
+#[derive(Copy, Clone, Debug, Default)]
+pub struct OrdF64(pub f64);
+
+impl PartialEq for OrdF64 {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OrdF64 {}
+
+impl PartialOrd for OrdF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrdF64 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl fmt::Display for OrdF64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Cost for OrdF64 {
+    fn zero() -> Self {
+        OrdF64(0.0)
+    }
+
+    fn plus(self, other: Self) -> Self {
+        OrdF64(self.0 + other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cost() {
+        assert_eq!((1u8, 2i64).plus((0, 3)), (1, 5));
+        assert!((0, 10) < (1, 0));
+        assert_eq!(OrdF64(0.5).plus(OrdF64(0.25)), OrdF64(0.75));
+        assert!(OrdF64(1.0) < OrdF64(f64::NAN));
+        assert!(OrdF64(-0.0) < OrdF64(0.0));
+        assert_eq!(5i64.plus(i64::MAX), i64::MAX);
+        assert_eq!(200u8.plus(100), u8::MAX);
+    }
+}
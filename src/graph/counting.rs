@@ -0,0 +1,182 @@
+use super::Cost;
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// Dijkstra from `start` that also counts the distinct cheapest paths to every reachable node.
+/// Returns `(cost, number of paths)` per node. Paths that use different edges between the same
+/// nodes count separately. Costs must be positive, since zero cost cycles would make the count
+/// infinite.
+pub fn count_shortest_paths<Node, Edge, Neighbors, C>(
+    start: &Node,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    distance: impl Fn(&Node, &Edge, &Node) -> C,
+) -> HashMap<Node, (C, u128)>
+where
+    Node: Clone + Eq + Hash,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    C: Cost,
+{
+    let mut ids = HashMap::from([(start.clone(), 0)]);
+    let mut nodes = vec![start.clone()];
+    let mut best = vec![(C::zero(), 1u128)];
+    let mut queue = BinaryHeap::from([Reverse((C::zero(), 0))]);
+
+    while let Some(Reverse((d, u))) = queue.pop() {
+        if d > best[u].0 {
+            continue;
+        }
+        let count = best[u].1;
+        let node = nodes[u].clone();
+        for (neighbor, edge) in get_neighbors(&node) {
+            let nd = d.plus(distance(&node, &edge, &neighbor));
+            match ids.entry(neighbor) {
+                Entry::Vacant(e) => {
+                    nodes.push(e.key().clone());
+                    e.insert(best.len());
+                    queue.push(Reverse((nd, best.len())));
+                    best.push((nd, count));
+                }
+                Entry::Occupied(e) => {
+                    let v = *e.get();
+                    if nd < best[v].0 {
+                        best[v] = (nd, count);
+                        queue.push(Reverse((nd, v)));
+                    } else if nd == best[v].0 {
+                        best[v].1 += count;
+                    }
+                }
+            }
+        }
+    }
+
+    nodes.into_iter().zip(best).collect()
+}
+
+/// [count_shortest_paths] where every step costs 1: the number of steps and the number of
+/// distinct shortest paths to every node reachable from `start`.
+pub fn count_shortest_paths_bfs<Node, Neighbors>(
+    start: &Node,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+) -> HashMap<Node, (usize, u128)>
+where
+    Node: Clone + Eq + Hash,
+    Neighbors: IntoIterator<Item = Node>,
+{
+    let mut best = HashMap::from([(start.clone(), (0, 1u128))]);
+    let mut frontier = VecDeque::from([start.clone()]);
+
+    while let Some(node) = frontier.pop_front() {
+        let (d, count) = best[&node];
+        for neighbor in get_neighbors(&node) {
+            match best.entry(neighbor) {
+                Entry::Vacant(e) => {
+                    frontier.push_back(e.key().clone());
+                    e.insert((d + 1, count));
+                }
+                Entry::Occupied(mut e) if e.get().0 == d + 1 => e.get_mut().1 += count,
+                Entry::Occupied(_) => {}
+            }
+        }
+    }
+
+    best
+}
+
+/// Number of paths from every node reachable from `roots` to a goal, in a graph without cycles,
+/// like a height map that can only be climbed. A goal counts as a path of its own, paths may
+/// continue past it. Panics if there is a cycle.
+pub fn count_dag_paths<Node, Neighbors>(
+    roots: impl IntoIterator<Item = Node>,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+) -> HashMap<Node, u128>
+where
+    Node: Clone + Eq + Hash,
+    Neighbors: IntoIterator<Item = Node>,
+{
+    let mut counts = HashMap::new();
+    let mut on_stack = HashSet::new();
+    for root in roots {
+        if counts.contains_key(&root) {
+            continue;
+        }
+        on_stack.insert(root.clone());
+        let neighbors: Vec<_> = get_neighbors(&root).into_iter().collect();
+        // every node on the path from the root, its neighbors and how many of them were visited.
+        let mut stack = vec![(root, neighbors, 0)];
+        while let Some((_, neighbors, i)) = stack.last_mut() {
+            if let Some(next) = neighbors.get(*i).cloned() {
+                *i += 1;
+                if counts.contains_key(&next) {
+                    continue;
+                }
+                assert!(on_stack.insert(next.clone()), "graph has a cycle");
+                let next_neighbors = get_neighbors(&next).into_iter().collect();
+                stack.push((next, next_neighbors, 0));
+                continue;
+            }
+            let (node, neighbors, _) = stack.pop().unwrap();
+            // every neighbor has been counted by now.
+            let total = neighbors.iter().map(|n| counts[n]).sum::<u128>() + is_goal(&node) as u128;
+            on_stack.remove(&node);
+            counts.insert(node, total);
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// an open square grid, where the number of shortest paths between opposite corners is a
+    /// binomial coefficient.
+    fn neighbors(n: isize) -> impl Fn(&[isize; 2]) -> Vec<[isize; 2]> {
+        move |&[x, y]| {
+            [[x + 1, y], [x, y + 1], [x - 1, y], [x, y - 1]]
+                .into_iter()
+                .filter(|&[x, y]| (0..n).contains(&x) && (0..n).contains(&y))
+                .collect()
+        }
+    }
+
+    fn binomial(n: u128, k: u128) -> u128 {
+        (1..=k).fold(1, |acc, i| acc * (n - k + i) / i)
+    }
+
+    #[test]
+    fn test_count_shortest_paths() {
+        let n = 60;
+        let counts = count_shortest_paths_bfs(&[0, 0], neighbors(n));
+        assert_eq!(counts[&[n - 1, n - 1]], (118, binomial(118, 59)));
+        assert_eq!(counts[&[2, 1]], (3, 3));
+
+        let counts = count_shortest_paths(
+            &[0, 0],
+            |p| neighbors(n)(p).into_iter().map(|q| (q, ())),
+            |_, _, _| 1,
+        );
+        assert_eq!(counts[&[n - 1, n - 1]], (118, binomial(118, 59)));
+        // moving down is cheap only in the first column, so only one cheapest path remains.
+        let counts = count_shortest_paths(
+            &[0, 0],
+            |p| neighbors(5)(p).into_iter().map(|q| (q, ())),
+            |a, _, b| if a[1] < b[1] && a[0] > 0 { 3 } else { 1 },
+        );
+        assert_eq!(counts[&[2, 2]], (4, 1));
+        assert_eq!(counts[&[2, 0]], (2, 1));
+    }
+
+    #[test]
+    fn test_count_dag_paths() {
+        // 0 -> 1 -> 3, 0 -> 2 -> 3, 3 -> 4, 1 -> 4, where 3 and 4 are goals.
+        let edges: [&[u32]; 5] = [&[1, 2], &[3, 4], &[3], &[4], &[]];
+        let counts = count_dag_paths([0], |&n| n >= 3, |&n| edges[n as usize].to_vec());
+        assert_eq!(counts[&4], 1);
+        assert_eq!(counts[&3], 2);
+        assert_eq!(counts[&1], 3);
+        assert_eq!(counts[&0], 5);
+    }
+}
@@ -0,0 +1,350 @@
+#[cfg(feature = "check-heuristic")]
+use super::a_star::{check_consistent, check_goal};
+use super::{Cost, NoPathFound, Path, SearchObserver};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::hash::Hash;
+
+struct Frame<Node, Edge, C> {
+    node: Node,
+    /// the edge from the previous frame's node to this one.
+    edge: Option<Edge>,
+    g: C,
+    neighbors: std::vec::IntoIter<(Node, Edge)>,
+}
+
+enum Outcome<Node, Edge, C> {
+    Found(Vec<(Node, Edge)>, Node),
+    Exhausted {
+        /// lowest estimate above the bound, the bound for the next iteration.
+        next_bound: Option<C>,
+    },
+}
+
+/// Depth first search that skips nodes whose estimate is above `bound` or that are deeper than
+/// `max_depth`, and nodes already seen with a lower or equal cost and depth, remembering up to
+/// `max_transpositions` of those.
+struct BoundedDfs<'a, Node, C, IsGoal, GetNeighbors, Heuristic, Distance, Observer> {
+    is_goal: &'a IsGoal,
+    get_neighbors: &'a GetNeighbors,
+    heuristic: &'a Heuristic,
+    distance: &'a Distance,
+    bound: Option<C>,
+    max_depth: Option<usize>,
+    max_transpositions: Option<usize>,
+    observer: &'a mut Observer,
+    table: HashMap<Node, (C, usize)>,
+    next_bound: Option<C>,
+    expanded: u64,
+    /// neighbors on the stack that are still to be tried, the frontier of a depth first search.
+    pending: usize,
+}
+
+impl<Node, Edge, Neighbors, C, IsGoal, GetNeighbors, Heuristic, Distance, Observer>
+    BoundedDfs<'_, Node, C, IsGoal, GetNeighbors, Heuristic, Distance, Observer>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    C: Cost,
+    IsGoal: Fn(&Node) -> bool,
+    GetNeighbors: Fn(&Node) -> Neighbors,
+    Heuristic: Fn(&Node) -> C,
+    Distance: Fn(&Node, &Edge, &Node) -> C,
+    Observer: SearchObserver<Node>,
+{
+    /// whether `node`, reached with cost `g` at `depth`, should be expanded.
+    fn admit(&mut self, node: &Node, g: C, depth: usize) -> bool {
+        let f = g.plus((self.heuristic)(node));
+        if let Some(bound) = self.bound
+            && f > bound
+        {
+            if self.next_bound.is_none_or(|b| f < b) {
+                self.next_bound = Some(f);
+            }
+            return false;
+        }
+        if self.max_depth.is_some_and(|d| depth > d) {
+            return false;
+        }
+        let full = self
+            .max_transpositions
+            .is_some_and(|m| self.table.len() >= m);
+        match self.table.entry(node.clone()) {
+            Entry::Occupied(e) if e.get().0 <= g && e.get().1 <= depth => false,
+            Entry::Occupied(mut e) => {
+                e.insert((g, depth));
+                true
+            }
+            Entry::Vacant(e) => {
+                if !full {
+                    e.insert((g, depth));
+                }
+                true
+            }
+        }
+    }
+
+    fn expand(&mut self, node: Node, edge: Option<Edge>, g: C) -> Frame<Node, Edge, C> {
+        let neighbors: Vec<_> = (self.get_neighbors)(&node).into_iter().collect();
+        self.pending += neighbors.len();
+        self.observer.on_expand(&node, self.pending);
+        self.expanded += 1;
+        Frame {
+            node,
+            edge,
+            g,
+            neighbors: neighbors.into_iter(),
+        }
+    }
+
+    fn run(&mut self, start: &Node) -> Outcome<Node, Edge, C> {
+        if !self.admit(start, C::zero(), 0) {
+            return Outcome::Exhausted {
+                next_bound: self.next_bound,
+            };
+        }
+        if (self.is_goal)(start) {
+            #[cfg(feature = "check-heuristic")]
+            check_goal((self.heuristic)(start));
+            return Outcome::Found(Vec::new(), start.clone());
+        }
+        let mut stack = vec![self.expand(start.clone(), None, C::zero())];
+        while let Some(frame) = stack.last_mut() {
+            let Some((neighbor, edge)) = frame.neighbors.next() else {
+                stack.pop();
+                continue;
+            };
+            self.pending -= 1;
+            let current = &frame.node;
+            let d = (self.distance)(current, &edge, &neighbor);
+            let g = frame.g.plus(d);
+            if stack.iter().any(|f| f.node == neighbor) {
+                continue;
+            }
+            let depth = stack.len();
+            if !self.admit(&neighbor, g, depth) {
+                continue;
+            }
+            let current = &stack[depth - 1].node;
+            self.observer.on_relax(current, &neighbor);
+            #[cfg(feature = "check-heuristic")]
+            check_consistent((self.heuristic)(current), d, (self.heuristic)(&neighbor));
+            if (self.is_goal)(&neighbor) {
+                #[cfg(feature = "check-heuristic")]
+                check_goal((self.heuristic)(&neighbor));
+                let mut rev = Vec::with_capacity(stack.len());
+                let mut edge = edge;
+                while let Some(frame) = stack.pop() {
+                    rev.push((frame.node, edge));
+                    let Some(e) = frame.edge else {
+                        break;
+                    };
+                    edge = e;
+                }
+                return Outcome::Found(rev, neighbor);
+            }
+            let frame = self.expand(neighbor, Some(edge), g);
+            stack.push(frame);
+        }
+        Outcome::Exhausted {
+            next_bound: self.next_bound,
+        }
+    }
+}
+
+/// Iterative deepening A*: repeated depth first searches, each bounded by the lowest estimate
+/// (cost so far plus `heuristic`) that the previous one skipped. Uses memory proportional to the
+/// path length plus at most `max_transpositions` remembered nodes (unbounded if `None`), at the
+/// price of expanding nodes again in every iteration. `heuristic` must never overestimate.
+///
+/// With the `check-heuristic` feature, panics like [a_star_rev_limited](super::a_star_rev_limited)
+/// if the heuristic is not consistent on an admitted edge or not zero at the goal.
+///
+/// `observer` is told about every finished iteration. Its frontier is the number of neighbors
+/// on the depth first search's stack that are still to be tried.
+pub fn ida_star<Node, Edge, Neighbors, C>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    heuristic: impl Fn(&Node) -> C,
+    distance: impl Fn(&Node, &Edge, &Node) -> C,
+    max_transpositions: Option<usize>,
+    mut observer: impl SearchObserver<Node>,
+) -> Result<Path<Node, Edge, C>, NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    C: Cost,
+{
+    let mut bound = heuristic(start);
+    for iteration in 0.. {
+        let mut dfs = BoundedDfs {
+            is_goal: &is_goal,
+            get_neighbors: &get_neighbors,
+            heuristic: &heuristic,
+            distance: &distance,
+            bound: Some(bound),
+            max_depth: None,
+            max_transpositions,
+            observer: &mut observer,
+            table: HashMap::new(),
+            next_bound: None,
+            expanded: 0,
+            pending: 0,
+        };
+        let outcome = dfs.run(start);
+        let expanded = dfs.expanded;
+        observer.on_iteration(iteration, expanded);
+        match outcome {
+            Outcome::Found(rev, goal) => {
+                observer.on_finish(true);
+                return Ok(Path::from_rev(rev, goal, distance));
+            }
+            Outcome::Exhausted {
+                next_bound: Some(next),
+            } => bound = next,
+            Outcome::Exhausted { next_bound: None } => break,
+        }
+    }
+    observer.on_finish(false);
+    Err(NoPathFound)
+}
+
+/// Depth first search for any path to a goal with at most `max_depth` steps, which need not be
+/// the cheapest one. Nodes are only visited again when they are reached in fewer steps or more
+/// cheaply, remembering at most `max_transpositions` of them (unbounded if `None`). The frontier
+/// reported to `observer` is the same as for [ida_star].
+pub fn depth_limited_search<Node, Edge, Neighbors, C>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    distance: impl Fn(&Node, &Edge, &Node) -> C,
+    max_depth: usize,
+    max_transpositions: Option<usize>,
+    mut observer: impl SearchObserver<Node>,
+) -> Result<Path<Node, Edge, C>, NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    C: Cost,
+{
+    let mut dfs = BoundedDfs {
+        is_goal: &is_goal,
+        get_neighbors: &get_neighbors,
+        heuristic: &|_: &Node| C::zero(),
+        distance: &distance,
+        bound: None,
+        max_depth: Some(max_depth),
+        max_transpositions,
+        observer: &mut observer,
+        table: HashMap::new(),
+        next_bound: None,
+        expanded: 0,
+        pending: 0,
+    };
+    let outcome = dfs.run(start);
+    let expanded = dfs.expanded;
+    observer.on_iteration(0, expanded);
+    let found = matches!(outcome, Outcome::Found(..));
+    observer.on_finish(found);
+    match outcome {
+        Outcome::Found(rev, goal) => Ok(Path::from_rev(rev, goal, distance)),
+        Outcome::Exhausted { .. } => Err(NoPathFound),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{SearchStats, a_star};
+
+    /// an open 6x6 grid where moving right costs 2 and every other step costs 1.
+    fn neighbors(&[x, y]: &[i32; 2]) -> Vec<([i32; 2], i64)> {
+        [
+            ([x + 1, y], 2),
+            ([x, y + 1], 1),
+            ([x - 1, y], 1),
+            ([x, y - 1], 1),
+        ]
+        .into_iter()
+        .filter(|([x, y], _)| (0..6).contains(x) && (0..6).contains(y))
+        .collect()
+    }
+
+    #[test]
+    fn test_ida_star() {
+        let goal = [5, 3];
+        let h = |&[x, y]: &[i32; 2]| (2 * (goal[0] - x).max(0) + (goal[1] - y).abs()) as i64;
+        let expected = a_star(&[0, 0], |&n| n == goal, neighbors, h, |_, &c, _| c).unwrap();
+        for cap in [None, Some(5), Some(0)] {
+            let mut stats = SearchStats::new();
+            let path = ida_star(
+                &[0, 0],
+                |&n| n == goal,
+                neighbors,
+                h,
+                |_, &c, _| c,
+                cap,
+                &mut stats,
+            )
+            .unwrap();
+            assert_eq!(path.cost(), expected.cost());
+            assert_eq!((path.start(), path.goal()), (&[0, 0], &goal));
+            assert_eq!(stats.found, Some(true));
+            assert!(!stats.iterations.is_empty());
+            assert_eq!(stats.iterations.iter().sum::<u64>(), stats.expanded);
+            // the neighbors left behind along the path, not just its depth.
+            assert!(stats.max_frontier > path.len());
+        }
+        let unreachable = ida_star(
+            &[0, 0],
+            |&n| n == [9, 9],
+            neighbors,
+            |_| 0,
+            |_, &c, _| c,
+            None,
+            (),
+        );
+        assert!(unreachable.is_err());
+    }
+
+    #[test]
+    fn test_depth_limited_search() {
+        let search = |max_depth| {
+            depth_limited_search(
+                &[0, 0],
+                |&n| n == [3, 2],
+                neighbors,
+                |_, &c, _| c,
+                max_depth,
+                None,
+                (),
+            )
+        };
+        assert!(search(4).is_err());
+        let path = search(5).unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!(path.cost(), 8);
+        assert!(search(9).unwrap().len() <= 9);
+    }
+
+    #[test]
+    #[cfg(feature = "check-heuristic")]
+    #[should_panic(expected = "heuristic is inconsistent on the edge last passed to on_relax")]
+    fn test_ida_star_inconsistent_heuristic() {
+        // counts a step to the right as 1 everywhere but at the start, where it overestimates.
+        let h = |&[x, y]: &[i32; 2]| if x == 0 { 20 } else { (5 - x + 3 - y) as i64 };
+        _ = ida_star(
+            &[0, 0],
+            |&n| n == [5, 3],
+            neighbors,
+            h,
+            |_, &c, _| c,
+            None,
+            (),
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Write};
+use std::hash::Hash;
+
+type NodeLabel<'a, N> = Box<dyn Fn(&N) -> String + 'a>;
+type EdgeLabel<'a, N, E> = Box<dyn Fn(&N, &E, &N) -> String + 'a>;
+
+/// How [to_dot] and [to_dot_with] draw a graph. By default the graph is undirected, nodes are
+/// labelled with their [Debug] representation and edges are not labelled.
+pub struct DotOptions<'a, N, E> {
+    pub directed: bool,
+    pub node_label: Option<NodeLabel<'a, N>>,
+    pub edge_label: Option<EdgeLabel<'a, N, E>>,
+    /// nodes of a path to draw in red, along with the edges between consecutive nodes.
+    pub highlight: Vec<N>,
+}
+
+impl<N, E> Default for DotOptions<'_, N, E> {
+    fn default() -> Self {
+        Self {
+            directed: false,
+            node_label: None,
+            edge_label: None,
+            highlight: Vec::new(),
+        }
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Graphviz DOT source for a graph given as `(from, to, edge)` triples. Nodes without edges are
+/// left out. Undirected graphs draw only the first edge between any two nodes, so edge lists
+/// that hold both directions are drawn once.
+pub fn to_dot<N, E>(
+    edges: impl IntoIterator<Item = (N, N, E)>,
+    options: &DotOptions<N, E>,
+) -> String
+where
+    N: Clone + Eq + Hash + Debug,
+{
+    dot(&[], edges, options)
+}
+
+/// [to_dot] that also draws `extra_nodes`, first and in their order, even if they have no edges.
+fn dot<N, E>(
+    extra_nodes: &[N],
+    edges: impl IntoIterator<Item = (N, N, E)>,
+    options: &DotOptions<N, E>,
+) -> String
+where
+    N: Clone + Eq + Hash + Debug,
+{
+    let mut ids: HashMap<N, usize> = HashMap::new();
+    let mut nodes = Vec::new();
+    let mut id = |n: &N| {
+        *ids.entry(n.clone()).or_insert_with(|| {
+            nodes.push(n.clone());
+            nodes.len() - 1
+        })
+    };
+    extra_nodes.iter().for_each(|n| _ = id(n));
+    let highlight: Vec<_> = options.highlight.iter().map(&mut id).collect();
+    let highlighted_edges: HashSet<_> = highlight
+        .windows(2)
+        .map(|w| match options.directed {
+            true => (w[0], w[1]),
+            false => (w[0].min(w[1]), w[0].max(w[1])),
+        })
+        .collect();
+    let mut drawn = HashSet::new();
+    let mut lines = Vec::new();
+    for (a, b, e) in edges {
+        let (i, j) = (id(&a), id(&b));
+        let key = if options.directed {
+            (i, j)
+        } else {
+            (i.min(j), i.max(j))
+        };
+        if !options.directed && !drawn.insert(key) {
+            continue;
+        }
+        let mut attrs = Vec::new();
+        if let Some(label) = &options.edge_label {
+            attrs.push(format!("label={}", quote(&label(&a, &e, &b))));
+        }
+        if highlighted_edges.contains(&key) {
+            attrs.push("color=red, penwidth=2".to_string());
+        }
+        let arrow = if options.directed { "->" } else { "--" };
+        let mut line = format!("    n{i} {arrow} n{j}");
+        if !attrs.is_empty() {
+            write!(line, " [{}]", attrs.join(", ")).unwrap();
+        }
+        lines.push(line);
+    }
+
+    let mut out = String::from(if options.directed {
+        "digraph {\n"
+    } else {
+        "graph {\n"
+    });
+    let highlight: HashSet<_> = highlight.into_iter().collect();
+    for (i, n) in nodes.iter().enumerate() {
+        let label = match &options.node_label {
+            Some(label) => label(n),
+            None => format!("{:?}", n),
+        };
+        write!(out, "    n{i} [label={}", quote(&label)).unwrap();
+        if highlight.contains(&i) {
+            out.push_str(", color=red, penwidth=2");
+        }
+        out.push_str("];\n");
+    }
+    for line in lines {
+        out.push_str(&line);
+        out.push_str(";\n");
+    }
+    out.push('}');
+    out
+}
+
+/// [to_dot] for a graph given by its nodes and a function that returns the neighbors of a node,
+/// like the searches take. Every node in `nodes` is drawn, with or without edges, and so are
+/// neighbors that are not in `nodes`.
+pub fn to_dot_with<N, E, Neighbors>(
+    nodes: impl IntoIterator<Item = N>,
+    get_neighbors: impl Fn(&N) -> Neighbors,
+    options: &DotOptions<N, E>,
+) -> String
+where
+    N: Clone + Eq + Hash + Debug,
+    Neighbors: IntoIterator<Item = (N, E)>,
+{
+    let nodes: Vec<_> = nodes.into_iter().collect();
+    let edges = nodes.iter().flat_map(|a| {
+        get_neighbors(a)
+            .into_iter()
+            .map(move |(b, e)| (a.clone(), b, e))
+            .collect::<Vec<_>>()
+    });
+    dot(&nodes, edges, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_dot() {
+        let edges = [("a", "b", 1), ("b", "c", 2), ("b", "a", 1)];
+        assert_eq!(
+            to_dot(edges, &DotOptions::default()),
+            "graph {\n    n0 [label=\"\\\"a\\\"\"];\n    n1 [label=\"\\\"b\\\"\"];\n    \
+             n2 [label=\"\\\"c\\\"\"];\n    n0 -- n1;\n    n1 -- n2;\n}"
+        );
+        let options = DotOptions {
+            directed: true,
+            node_label: Some(Box::new(|n: &&str| n.to_uppercase())),
+            edge_label: Some(Box::new(|_: &&str, c: &i32, _: &&str| c.to_string())),
+            highlight: vec!["b", "c"],
+        };
+        assert_eq!(
+            to_dot(edges, &options),
+            "digraph {\n    n0 [label=\"B\", color=red, penwidth=2];\n    \
+             n1 [label=\"C\", color=red, penwidth=2];\n    n2 [label=\"A\"];\n    \
+             n2 -> n0 [label=\"1\"];\n    n0 -> n1 [label=\"2\", color=red, penwidth=2];\n    \
+             n0 -> n2 [label=\"1\"];\n}"
+        );
+    }
+
+    #[test]
+    fn test_to_dot_with() {
+        // a 2x2 grid, with the path along the top and right side highlighted.
+        let neighbors = |&[x, y]: &[i32; 2]| {
+            [[x + 1, y], [x, y + 1], [x - 1, y], [x, y - 1]]
+                .into_iter()
+                .filter(|&[x, y]| (0..2).contains(&x) && (0..2).contains(&y))
+                .map(|n| (n, ()))
+        };
+        let options = DotOptions {
+            node_label: Some(Box::new(|&[x, y]: &[i32; 2]| format!("{x},{y}"))),
+            highlight: vec![[0, 0], [1, 0], [1, 1]],
+            ..Default::default()
+        };
+        let dot = to_dot_with([[0, 0], [1, 0], [0, 1], [1, 1]], neighbors, &options);
+        assert_eq!(dot.matches(" -- ").count(), 4);
+        assert_eq!(dot.matches("color=red").count(), 5);
+        assert!(dot.contains("n2 [label=\"0,1\"];"));
+
+        // nodes without edges are drawn too.
+        let dot = to_dot_with(
+            [1, 2, 3],
+            |&n| (n == 1).then_some((2, ())),
+            &DotOptions::default(),
+        );
+        assert_eq!(
+            dot,
+            "graph {\n    n0 [label=\"1\"];\n    n1 [label=\"2\"];\n    n2 [label=\"3\"];\n    \
+             n0 -- n1;\n}"
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use crate::grid::{Grid, Pos, idx};
+use std::collections::VecDeque;
+
+/// Capacity that is never the bottleneck, but still leaves room for sums without overflowing.
+pub const INFINITE_CAPACITY: i64 = i64::MAX / 4;
+
+/// Directed graph with integer edge capacities on the nodes `0..n`.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct FlowNetwork {
+    adjacency: Vec<Vec<usize>>,
+    /// edge `2 * id` is the edge added by the user, `2 * id + 1` its residual.
+    to: Vec<usize>,
+    capacity: Vec<i64>,
+}
+
+/// Result of [FlowNetwork::max_flow].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct MaxFlow {
+    pub value: i64,
+    /// flow through every edge, by the id returned from [FlowNetwork::add_edge].
+    pub flows: Vec<i64>,
+    /// whether a node is on the source side of the minimum cut.
+    pub source_side: Vec<bool>,
+    /// ids of the edges crossing the minimum cut. Their capacities add up to `value`.
+    pub cut: Vec<usize>,
+}
+
+impl FlowNetwork {
+    pub fn new(n: usize) -> Self {
+        Self {
+            adjacency: vec![Vec::new(); n],
+            to: Vec::new(),
+            capacity: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.adjacency.is_empty()
+    }
+
+    /// Adds an edge and returns its id. Ids are assigned consecutively from 0.
+    pub fn add_edge(&mut self, from: usize, to: usize, capacity: i64) -> usize {
+        assert!(capacity >= 0);
+        let e = self.to.len();
+        self.adjacency[from].push(e);
+        self.to.push(to);
+        self.capacity.push(capacity);
+        self.adjacency[to].push(e + 1);
+        self.to.push(from);
+        self.capacity.push(0);
+        e / 2
+    }
+
+    /// Dinic's algorithm.
+    pub fn max_flow(&self, source: usize, sink: usize) -> MaxFlow {
+        assert_ne!(source, sink);
+        let n = self.len();
+        let mut residual = self.capacity.clone();
+        let mut value = 0;
+        let mut level = vec![usize::MAX; n];
+        loop {
+            level.fill(usize::MAX);
+            level[source] = 0;
+            let mut queue = VecDeque::from([source]);
+            while let Some(u) = queue.pop_front() {
+                for &e in &self.adjacency[u] {
+                    let v = self.to[e];
+                    if residual[e] > 0 && level[v] == usize::MAX {
+                        level[v] = level[u] + 1;
+                        queue.push_back(v);
+                    }
+                }
+            }
+            if level[sink] == usize::MAX {
+                break;
+            }
+
+            // blocking flow, searching depth first without recursion.
+            let mut next_edge = vec![0; n];
+            let mut path: Vec<usize> = Vec::new();
+            let mut u = source;
+            loop {
+                if u == sink {
+                    let bottleneck = path.iter().map(|&e| residual[e]).min().unwrap();
+                    for &e in &path {
+                        residual[e] -= bottleneck;
+                        residual[e ^ 1] += bottleneck;
+                    }
+                    value += bottleneck;
+                    path.clear();
+                    u = source;
+                    continue;
+                }
+                let advance = self.adjacency[u][next_edge[u]..]
+                    .iter()
+                    .position(|&e| residual[e] > 0 && level[self.to[e]] == level[u] + 1);
+                match advance {
+                    Some(i) => {
+                        next_edge[u] += i;
+                        let e = self.adjacency[u][next_edge[u]];
+                        path.push(e);
+                        u = self.to[e];
+                    }
+                    None => {
+                        next_edge[u] = self.adjacency[u].len();
+                        // nothing reaches the sink through `u` anymore in this phase.
+                        level[u] = usize::MAX;
+                        let Some(e) = path.pop() else {
+                            break;
+                        };
+                        u = self.to[e ^ 1];
+                    }
+                }
+            }
+        }
+
+        let mut source_side = vec![false; n];
+        source_side[source] = true;
+        let mut queue = VecDeque::from([source]);
+        while let Some(u) = queue.pop_front() {
+            for &e in &self.adjacency[u] {
+                let v = self.to[e];
+                if residual[e] > 0 && !source_side[v] {
+                    source_side[v] = true;
+                    queue.push_back(v);
+                }
+            }
+        }
+        let cut = (0..self.to.len() / 2)
+            .filter(|&id| {
+                let (from, to) = (self.to[2 * id + 1], self.to[2 * id]);
+                source_side[from] && !source_side[to]
+            })
+            .collect();
+        let flows = (0..self.to.len() / 2)
+            .map(|id| self.capacity[2 * id] - residual[2 * id])
+            .collect();
+
+        MaxFlow {
+            value,
+            flows,
+            source_side,
+            cut,
+        }
+    }
+}
+
+/// Network with a capacity on every passable cell instead of on the edges between them. Each cell
+/// is split into an entry node `2 * idx(p)` and an exit node `2 * idx(p) + 1`, connected by an edge
+/// with id `idx(p)` and the cell's capacity. Exits are connected to the entries of passable
+/// 4-connected neighbors with [INFINITE_CAPACITY]. Flow from `a` to `b` therefore runs from
+/// `2 * idx(a) + 1` to `2 * idx(b)`.
+pub fn grid_flow_network<Cell>(
+    grid: &Grid<Cell>,
+    is_passable: impl Fn(Pos, &Cell) -> bool,
+    capacity: impl Fn(Pos, &Cell) -> i64,
+) -> FlowNetwork {
+    let mut net = FlowNetwork::new(2 * grid.len());
+    for (p, c) in grid.iter_pos() {
+        let i = idx(p, grid.size);
+        let cap = if is_passable(p, c) { capacity(p, c) } else { 0 };
+        net.add_edge(2 * i, 2 * i + 1, cap);
+    }
+    for (p @ [x, y], c) in grid.iter_pos() {
+        if !is_passable(p, c) {
+            continue;
+        }
+        for n in [[x + 1, y], [x, y + 1], [x - 1, y], [x, y - 1]] {
+            if grid.is_inside(n) && is_passable(n, &grid[n]) {
+                let (a, b) = (idx(p, grid.size), idx(n, grid.size));
+                net.add_edge(2 * a + 1, 2 * b, INFINITE_CAPACITY);
+            }
+        }
+    }
+    net
+}
+
+/// Fewest passable cells that have to be blocked so that `goal` can not be reached from `start`,
+/// and which cells those are. Panics if `start` and `goal` are adjacent.
+pub fn grid_min_vertex_cut<Cell>(
+    grid: &Grid<Cell>,
+    is_passable: impl Fn(Pos, &Cell) -> bool,
+    start: Pos,
+    goal: Pos,
+) -> (i64, Vec<Pos>) {
+    let net = grid_flow_network(grid, is_passable, |p, _| {
+        if p == start || p == goal {
+            INFINITE_CAPACITY
+        } else {
+            1
+        }
+    });
+    let flow = net.max_flow(2 * idx(start, grid.size) + 1, 2 * idx(goal, grid.size));
+    assert!(
+        flow.value < INFINITE_CAPACITY,
+        "start and goal are adjacent"
+    );
+    let width = grid.width();
+    let cells = flow
+        .cut
+        .iter()
+        .map(|&id| [(id % width) as isize, (id / width) as isize])
+        .collect();
+    (flow.value, cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_flow() {
+        let mut net = FlowNetwork::new(6);
+        for (a, b, c) in [
+            (0, 1, 16),
+            (0, 2, 13),
+            (2, 1, 4),
+            (1, 3, 12),
+            (3, 2, 9),
+            (2, 4, 14),
+            (4, 3, 7),
+            (3, 5, 20),
+            (4, 5, 4),
+        ] {
+            net.add_edge(a, b, c);
+        }
+        let flow = net.max_flow(0, 5);
+        assert_eq!(flow.value, 23);
+        assert_eq!(flow.source_side, [true, true, true, false, true, false]);
+        assert_eq!(flow.cut, [3, 6, 8]);
+        assert_eq!(flow.cut.iter().map(|&e| flow.flows[e]).sum::<i64>(), 23);
+    }
+
+    #[test]
+    fn test_grid_min_vertex_cut() {
+        let grid = Grid::from_lines(
+            [
+                "S....#...", //
+                ".....#...",
+                ".........",
+                ".....#...",
+                ".....#..G",
+            ],
+            |_, c| c == '#',
+        );
+        let (cut, cells) = grid_min_vertex_cut(&grid, |_, &wall| !wall, [0, 0], [8, 4]);
+        assert_eq!(cut, 1);
+        assert_eq!(cells, [[4, 2]]);
+    }
+}
@@ -0,0 +1,192 @@
+use super::{NoPathFound, Path};
+use crate::grid::{Grid, Pos};
+use std::collections::BTreeSet;
+
+const INF: i64 = i64::MAX / 4;
+
+type Key = (i64, i64);
+
+/// Lifelong Planning A* on a 4-connected grid where every step costs 1: keeps the search state
+/// between calls, so that after blocking a cell with [block](Self::block) only the part of the
+/// search that depended on it is redone.
+#[derive(Clone, Debug)]
+pub struct IncrementalPlanner {
+    blocked: Grid<bool>,
+    start: Pos,
+    goal: Pos,
+    /// cost of the cheapest path found so far.
+    g: Grid<i64>,
+    /// one step further than the cheapest neighbor's `g`.
+    rhs: Grid<i64>,
+    queue: BTreeSet<(Key, Pos)>,
+    /// the key under which each cell is queued.
+    queued: Grid<Option<Key>>,
+}
+
+impl IncrementalPlanner {
+    pub fn new<Cell>(
+        grid: &Grid<Cell>,
+        is_passable: impl Fn(Pos, &Cell) -> bool,
+        start: Pos,
+        goal: Pos,
+    ) -> Self {
+        let mut blocked = Grid::new(grid.size);
+        for p in grid.positions() {
+            blocked[p] = !is_passable(p, &grid[p]);
+        }
+        let mut planner = Self {
+            blocked,
+            start,
+            goal,
+            g: Grid::new_with(grid.size, || INF),
+            rhs: Grid::new_with(grid.size, || INF),
+            queue: BTreeSet::new(),
+            queued: Grid::new(grid.size),
+        };
+        if !planner.blocked[start] {
+            planner.rhs[start] = 0;
+            planner.enqueue(start);
+        }
+        planner
+    }
+
+    fn h(&self, [x, y]: Pos) -> i64 {
+        ((x - self.goal[0]).abs() + (y - self.goal[1]).abs()) as i64
+    }
+
+    fn key(&self, p: Pos) -> Key {
+        let m = self.g[p].min(self.rhs[p]);
+        (m.saturating_add(self.h(p)), m)
+    }
+
+    fn enqueue(&mut self, p: Pos) {
+        let key = self.key(p);
+        self.queue.insert((key, p));
+        self.queued[p] = Some(key);
+    }
+
+    fn neighbors(&self, [x, y]: Pos) -> impl Iterator<Item = Pos> + '_ {
+        [[x + 1, y], [x, y + 1], [x - 1, y], [x, y - 1]]
+            .into_iter()
+            .filter(|&n| self.blocked.is_inside(n) && !self.blocked[n])
+    }
+
+    fn update(&mut self, p: Pos) {
+        if p != self.start {
+            self.rhs[p] = if self.blocked[p] {
+                INF
+            } else {
+                self.neighbors(p)
+                    .map(|n| self.g[n] + 1)
+                    .min()
+                    .unwrap_or(INF)
+                    .min(INF)
+            };
+        }
+        if let Some(key) = self.queued[p].take() {
+            self.queue.remove(&(key, p));
+        }
+        if self.g[p] != self.rhs[p] {
+            self.enqueue(p);
+        }
+    }
+
+    fn compute(&mut self) {
+        while let Some(&(key, p)) = self.queue.first() {
+            if key >= self.key(self.goal) && self.rhs[self.goal] == self.g[self.goal] {
+                break;
+            }
+            self.queue.pop_first();
+            self.queued[p] = None;
+            let next: Vec<_> = self.neighbors(p).collect();
+            if self.g[p] > self.rhs[p] {
+                self.g[p] = self.rhs[p];
+            } else {
+                self.g[p] = INF;
+                self.update(p);
+            }
+            for n in next {
+                self.update(n);
+            }
+        }
+    }
+
+    /// Makes `pos` impassable.
+    pub fn block(&mut self, pos: Pos) {
+        if self.blocked[pos] {
+            return;
+        }
+        self.blocked[pos] = true;
+        if pos == self.start {
+            self.rhs[pos] = INF;
+        }
+        self.update(pos);
+        let next: Vec<_> = self.neighbors(pos).collect();
+        for n in next {
+            self.update(n);
+        }
+    }
+
+    /// length of the current shortest path, if there is one.
+    pub fn cost(&mut self) -> Option<i64> {
+        self.compute();
+        let g = self.g[self.goal];
+        (g < INF).then_some(g)
+    }
+
+    /// the current shortest path.
+    pub fn path(&mut self) -> Result<Path<Pos, ()>, NoPathFound> {
+        self.cost().ok_or(NoPathFound)?;
+        let mut rev = Vec::new();
+        let mut p = self.goal;
+        while p != self.start {
+            p = self.neighbors(p).min_by_key(|&n| self.g[n]).unwrap();
+            rev.push((p, ()));
+        }
+        Ok(Path::from_rev(rev, self.goal, |_, _, _| 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::grid_a_star;
+
+    #[test]
+    fn test_incremental_planner() {
+        let mut blocked = Grid::from_lines([".......", ".......", ".......", "......."], |_, c| {
+            c == '#'
+        });
+        let (start, goal) = ([0, 0], [6, 3]);
+        let mut planner = IncrementalPlanner::new(&blocked, |_, &b| !b, start, goal);
+        assert_eq!(planner.cost(), Some(9));
+        let walls = [
+            [1, 0],
+            [1, 1],
+            [1, 2],
+            [3, 3],
+            [3, 2],
+            [3, 1],
+            [5, 0],
+            [5, 1],
+            [0, 3],
+            [5, 2],
+            [6, 2],
+            [4, 2],
+            [5, 3],
+        ];
+        for w in walls {
+            blocked[w] = true;
+            planner.block(w);
+            let expected = grid_a_star(&blocked, |_, &b| !b, start, goal, ())
+                .ok()
+                .map(|p| p.cost());
+            assert_eq!(planner.cost(), expected, "after blocking {:?}", w);
+            if let Ok(path) = planner.path() {
+                assert_eq!(Some(path.cost()), expected);
+                assert!(path.nodes().iter().all(|&p| !blocked[p]));
+            }
+        }
+        assert!(planner.path().is_err());
+    }
+}
@@ -0,0 +1,435 @@
+#[cfg(feature = "check-heuristic")]
+use super::a_star::{check_consistent, check_goal};
+use super::{
+    Cost, Limit, NoPathFound, Path, SearchAborted, SearchError, SearchLimits, SearchObserver,
+};
+use crate::grid::{Pos, Size, idx};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::time::Instant;
+
+/// Nodes that map to `0..count(space)`, so searches can keep their state in flat vectors instead
+/// of hash maps. The `space` is whatever is needed to compute the index, like the size of a
+/// [Grid](crate::grid::Grid) for a [Pos].
+///
+/// Small enums, like directions, can implement this with `Space = ()`, which makes `(Pos, Dir)`
+/// an index as well.
+pub trait NodeIndex {
+    type Space: Copy;
+
+    fn count(space: Self::Space) -> usize;
+
+    fn index(&self, space: Self::Space) -> usize;
+}
+
+impl NodeIndex for Pos {
+    type Space = Size;
+
+    fn count([width, height]: Size) -> usize {
+        width * height
+    }
+
+    fn index(&self, size: Size) -> usize {
+        idx(*self, size)
+    }
+}
+
+impl NodeIndex for usize {
+    /// the number of nodes.
+    type Space = usize;
+
+    fn count(space: usize) -> usize {
+        space
+    }
+
+    fn index(&self, space: usize) -> usize {
+        assert!(*self < space);
+        *self
+    }
+}
+
+impl<N: NodeIndex, D: NodeIndex<Space = ()>> NodeIndex for (N, D) {
+    type Space = N::Space;
+
+    fn count(space: N::Space) -> usize {
+        N::count(space) * D::count(())
+    }
+
+    fn index(&self, space: N::Space) -> usize {
+        self.0.index(space) * D::count(()) + self.1.index(())
+    }
+}
+
+/// [a_star](super::a_star) for nodes with a [NodeIndex], storing the scores in vectors of
+/// `count(space)` entries and the open set in a binary heap.
+pub fn a_star_indexed<Node, Edge, Neighbors, C>(
+    start: &Node,
+    space: Node::Space,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    heuristic: impl Fn(&Node) -> C,
+    distance: impl Fn(&Node, &Edge, &Node) -> C,
+) -> Result<Path<Node, Edge, C>, NoPathFound>
+where
+    Node: NodeIndex + Clone,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    C: Cost,
+{
+    let (rev, goal) =
+        a_star_indexed_rev(start, space, is_goal, get_neighbors, heuristic, &distance)?;
+    Ok(Path::from_rev(rev, goal, distance))
+}
+
+/// [a_star_indexed] that gives up once one of the `limits` is reached, see
+/// [a_star_indexed_rev_limited].
+#[allow(clippy::too_many_arguments)]
+pub fn a_star_indexed_limited<Node, Edge, Neighbors, C>(
+    start: &Node,
+    space: Node::Space,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    heuristic: impl Fn(&Node) -> Option<C>,
+    distance: impl Fn(&Node, &Edge, &Node) -> C,
+    limits: &SearchLimits<C>,
+    observer: impl SearchObserver<Node>,
+) -> Result<Path<Node, Edge, C>, SearchError<Node>>
+where
+    Node: NodeIndex + Clone,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    C: Cost,
+{
+    let (rev, goal) = a_star_indexed_rev_limited(
+        start,
+        space,
+        is_goal,
+        get_neighbors,
+        heuristic,
+        &distance,
+        limits,
+        observer,
+    )?;
+    Ok(Path::from_rev(rev, goal, distance))
+}
+
+/// [a_star_rev](super::a_star_rev) for nodes with a [NodeIndex], see [a_star_indexed].
+pub fn a_star_indexed_rev<Node, Edge, Neighbors, C>(
+    start: &Node,
+    space: Node::Space,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    heuristic: impl Fn(&Node) -> C,
+    distance: impl Fn(&Node, &Edge, &Node) -> C,
+) -> Result<(Vec<(Node, Edge)>, Node), NoPathFound>
+where
+    Node: NodeIndex + Clone,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    C: Cost,
+{
+    a_star_indexed_rev_limited(
+        start,
+        space,
+        is_goal,
+        get_neighbors,
+        |n| Some(heuristic(n)),
+        distance,
+        &SearchLimits::default(),
+        (),
+    )
+    .map_err(|_| NoPathFound)
+}
+
+/// [a_star_rev_limited](super::a_star_rev_limited) for nodes with a [NodeIndex], see
+/// [a_star_indexed]. Checks the heuristic like it with the `check-heuristic` feature. The frontier reported to the observer counts every node once, however
+/// often it is in the heap.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn a_star_indexed_rev_limited<Node, Edge, Neighbors, C>(
+    start: &Node,
+    space: Node::Space,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    heuristic: impl Fn(&Node) -> Option<C>,
+    distance: impl Fn(&Node, &Edge, &Node) -> C,
+    limits: &SearchLimits<C>,
+    mut observer: impl SearchObserver<Node>,
+) -> Result<(Vec<(Node, Edge)>, Node), SearchError<Node>>
+where
+    Node: NodeIndex + Clone,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    C: Cost,
+{
+    let started = Instant::now();
+    let mut expanded = 0;
+    let Some(h) = heuristic(start) else {
+        observer.on_finish(false);
+        return Err(SearchError::NoPathFound);
+    };
+    let mut best = (h, start.clone());
+    let mut pruned = false;
+    let n = Node::count(space);
+    let mut nodes: Vec<Option<Node>> = (0..n).map(|_| None).collect();
+    let mut came_from: Vec<Option<(usize, Edge)>> = (0..n).map(|_| None).collect();
+    let mut g_score: Vec<Option<C>> = vec![None; n];
+    // `Some` exactly for the nodes in the open set.
+    let mut f_score: Vec<Option<C>> = vec![None; n];
+    let mut open_len = 1;
+    let mut open_set = BinaryHeap::new();
+
+    let s = start.index(space);
+    nodes[s] = Some(start.clone());
+    g_score[s] = Some(C::zero());
+    f_score[s] = Some(h);
+    open_set.push(Reverse((h, s)));
+
+    while let Some(Reverse((f, i))) = open_set.pop() {
+        if f_score[i] != Some(f) {
+            continue;
+        }
+        let current = nodes[i].clone().unwrap();
+        if is_goal(&current) {
+            #[cfg(feature = "check-heuristic")]
+            if let Some(h) = heuristic(&current) {
+                check_goal(h);
+            }
+            let mut rev = Vec::new();
+            let mut j = i;
+            while let Some((prev, edge)) = came_from[j].take() {
+                rev.push((nodes[prev].take().unwrap(), edge));
+                j = prev;
+            }
+            observer.on_finish(true);
+            return Ok((rev, current));
+        }
+
+        if let Some(limit) = limits.check(expanded, started) {
+            observer.on_finish(false);
+            return Err(SearchError::Aborted(SearchAborted {
+                limit,
+                best: best.1,
+                expanded,
+            }));
+        }
+
+        // so that a node is only expanded again if it is reached more cheaply.
+        f_score[i] = None;
+        open_len -= 1;
+        observer.on_expand(&current, open_len);
+        expanded += 1;
+        let current_h = heuristic(&current);
+        if let Some(h) = current_h
+            && h < best.0
+        {
+            best = (h, current.clone());
+        }
+        let g = g_score[i].unwrap();
+
+        for (neighbor, edge) in get_neighbors(&current) {
+            let d = distance(&current, &edge, &neighbor);
+            let tentative_g_score = g.plus(d);
+            let j = neighbor.index(space);
+            if g_score[j].is_some_and(|s| s <= tentative_g_score) {
+                continue;
+            }
+            let Some(h) = heuristic(&neighbor) else {
+                continue;
+            };
+            let f = tentative_g_score.plus(h);
+            if limits.exceeds_cost(f) {
+                pruned = true;
+                continue;
+            }
+            observer.on_relax(&current, &neighbor);
+            #[cfg(feature = "check-heuristic")]
+            if let Some(current_h) = current_h {
+                check_consistent(current_h, d, h);
+            }
+            if f_score[j].is_none() {
+                open_len += 1;
+            }
+            nodes[j] = Some(neighbor);
+            came_from[j] = Some((i, edge));
+            g_score[j] = Some(tentative_g_score);
+            f_score[j] = Some(f);
+            open_set.push(Reverse((f, j)));
+        }
+    }
+
+    observer.on_finish(false);
+    if pruned {
+        Err(SearchError::Aborted(SearchAborted {
+            limit: Limit::Cost,
+            best: best.1,
+            expanded,
+        }))
+    } else {
+        Err(SearchError::NoPathFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{SearchStats, a_star};
+    use crate::grid::Grid;
+
+    #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+    enum Dir {
+        East,
+        South,
+        West,
+        North,
+    }
+
+    impl NodeIndex for Dir {
+        type Space = ();
+
+        fn count(_: ()) -> usize {
+            4
+        }
+
+        fn index(&self, _: ()) -> usize {
+            *self as usize
+        }
+    }
+
+    impl Dir {
+        fn vec(self) -> Pos {
+            match self {
+                Dir::East => [1, 0],
+                Dir::South => [0, 1],
+                Dir::West => [-1, 0],
+                Dir::North => [0, -1],
+            }
+        }
+    }
+
+    #[test]
+    fn test_a_star_indexed() {
+        let grid = Grid::from_lines(
+            [
+                "......#...",
+                ".####.#.#.",
+                ".#....#.#.",
+                ".#.####.#.",
+                ".#......#.",
+                ".######.#.",
+                "........#.",
+            ],
+            |_, c| c == '#',
+        );
+        let goal = [9, 6];
+        let hamming = |a: Pos, b: Pos| (a[0] - b[0]).abs() + (a[1] - b[1]).abs();
+        // moving costs 1, turning costs 10, as (Pos, Dir) nodes.
+        let neighbors = |&(p, d): &(Pos, Dir)| {
+            [Dir::East, Dir::South, Dir::West, Dir::North]
+                .into_iter()
+                .map(move |n| (vecmath::vec2_add(p, n.vec()), n))
+                .filter(|&(q, _)| grid.is_inside(q) && !grid[q])
+                .map(move |(q, n)| ((q, n), if n == d { 1 } else { 11 }))
+                .collect::<Vec<_>>()
+        };
+        let h = |&(p, _): &(Pos, Dir)| hamming(p, goal) as i64;
+        let start = ([0, 0], Dir::East);
+        let expected = a_star(&start, |n| n.0 == goal, neighbors, h, |_, &c, _| c).unwrap();
+        let path = a_star_indexed(
+            &start,
+            grid.size,
+            |n| n.0 == goal,
+            neighbors,
+            h,
+            |_, &c, _| c,
+        )
+        .unwrap();
+        assert_eq!(path.cost(), expected.cost());
+        assert_eq!(path.start(), &start);
+        assert_eq!(path.goal().0, goal);
+        for (&(a, _), &(b, _)) in path.nodes().iter().zip(&path.nodes()[1..]) {
+            assert_eq!(hamming(a, b), 1);
+        }
+
+        let blocked = Grid::from_lines([".#", "#."], |_, c| c == '#');
+        let result = a_star_indexed(
+            &[0, 0],
+            blocked.size,
+            |&p| p == [1, 1],
+            |&[x, y]: &Pos| {
+                [[x + 1, y], [x, y + 1]]
+                    .into_iter()
+                    .filter(|&q| blocked.is_inside(q) && !blocked[q])
+                    .map(|q| (q, ()))
+            },
+            |_| 0,
+            |_, _, _| 1,
+        );
+        assert_eq!(result, Err(NoPathFound));
+    }
+
+    #[test]
+    fn test_a_star_indexed_limited() {
+        let search = |limits: &SearchLimits, stats: &mut SearchStats| {
+            a_star_indexed_limited(
+                &0,
+                101,
+                |&n| n == 100,
+                |&n| {
+                    [n + 1, n + 2]
+                        .into_iter()
+                        .filter(|&m| m <= 100)
+                        .map(|m| (m, ()))
+                },
+                |&n: &usize| Some((100 - n) as i64 / 2),
+                |_, _, _| 1,
+                limits,
+                stats,
+            )
+        };
+        let mut stats = SearchStats::new();
+        let path = search(&SearchLimits::default(), &mut stats).unwrap();
+        assert_eq!((path.cost(), path.start(), path.goal()), (50, &0, &100));
+        assert_eq!(stats.found, Some(true));
+        assert!(stats.expanded >= 50);
+
+        let limits = SearchLimits {
+            max_expanded: Some(10),
+            ..SearchLimits::default()
+        };
+        let Err(SearchError::Aborted(aborted)) = search(&limits, &mut SearchStats::new()) else {
+            panic!();
+        };
+        assert_eq!((aborted.limit, aborted.expanded), (Limit::Expanded, 10));
+        assert!(aborted.best > 0);
+        let limits = SearchLimits {
+            max_cost: Some(49),
+            ..SearchLimits::default()
+        };
+        assert!(matches!(
+            search(&limits, &mut SearchStats::new()),
+            Err(SearchError::Aborted(SearchAborted {
+                limit: Limit::Cost,
+                ..
+            }))
+        ));
+        // pruned by the heuristic.
+        let result = a_star_indexed_rev_limited(
+            &0,
+            10,
+            |&n| n == 9,
+            |&n| (n < 9).then_some((n + 1, ())),
+            |&n| (n != 5).then_some(0),
+            |_, _, _| 1,
+            &SearchLimits::default(),
+            (),
+        );
+        assert_eq!(result, Err(SearchError::NoPathFound));
+    }
+
+    #[test]
+    #[cfg(feature = "check-heuristic")]
+    #[should_panic(expected = "heuristic is not zero at the goal")]
+    fn test_a_star_indexed_nonzero_goal_heuristic() {
+        _ = a_star_indexed(&0, 10, |&n| n == 9, |&n| [(n + 1, ())], |_| 1, |_, _, _| 1);
+    }
+}
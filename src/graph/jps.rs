@@ -0,0 +1,274 @@
+use super::{
+    NoPathFound, NodeIndex, Path, SearchLimits, SearchObserver, a_star_indexed_limited,
+    a_star_observed,
+};
+use crate::grid::{Grid, Pos};
+
+fn manhattan(a: Pos, b: Pos) -> i64 {
+    ((a[0] - b[0]).abs() + (a[1] - b[1]).abs()) as i64
+}
+
+/// the direction a jump point was entered in.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum Dir {
+    Start,
+    East,
+    South,
+    West,
+    North,
+}
+
+impl Dir {
+    const MOVES: [Dir; 4] = [Dir::East, Dir::South, Dir::West, Dir::North];
+
+    fn vec(self) -> Pos {
+        match self {
+            Dir::Start => [0, 0],
+            Dir::East => [1, 0],
+            Dir::South => [0, 1],
+            Dir::West => [-1, 0],
+            Dir::North => [0, -1],
+        }
+    }
+
+    fn is_horizontal(self) -> bool {
+        matches!(self, Dir::East | Dir::West)
+    }
+}
+
+impl NodeIndex for Dir {
+    type Space = ();
+
+    fn count(_: ()) -> usize {
+        5
+    }
+
+    fn index(&self, _: ()) -> usize {
+        *self as usize
+    }
+}
+
+/// The jump from every cell in every direction, computed once per search with a sweep against
+/// each direction, so that no jump is scanned twice.
+struct Jumps {
+    /// indexed by [Dir::MOVES].
+    to: [Grid<Option<Pos>>; 4],
+}
+
+impl Jumps {
+    fn new(open: &Grid<bool>, goal: Pos) -> Self {
+        let is_open = |p: Pos| open.is_inside(p) && open[p];
+        let positions: Vec<_> = open.positions().collect();
+        let mut to: [Grid<Option<Pos>>; 4] = std::array::from_fn(|_| Grid::new(open.size));
+        // the horizontal jumps first, the vertical ones stop where a horizontal jump succeeds.
+        for i in [0, 2, 1, 3] {
+            let dir = Dir::MOVES[i];
+            let [dx, dy] = dir.vec();
+            // so that the cell a jump moves to is done before the cells it is entered from.
+            let order: Box<dyn Iterator<Item = &Pos>> = match dir {
+                Dir::East | Dir::South => Box::new(positions.iter().rev()),
+                _ => Box::new(positions.iter()),
+            };
+            for &p in order {
+                let n @ [x, y] = [p[0] + dx, p[1] + dy];
+                to[i][p] = if !is_open(n) {
+                    None
+                } else if n == goal {
+                    Some(n)
+                } else {
+                    let forced = if dir.is_horizontal() {
+                        (is_open([x, y - 1]) && !is_open([x - dx, y - 1]))
+                            || (is_open([x, y + 1]) && !is_open([x - dx, y + 1]))
+                    } else {
+                        (is_open([x - 1, y]) && !is_open([x - 1, y - dy]))
+                            || (is_open([x + 1, y]) && !is_open([x + 1, y - dy]))
+                            || to[0][n].is_some()
+                            || to[2][n].is_some()
+                    };
+                    if forced { Some(n) } else { to[i][n] }
+                };
+            }
+        }
+        Self { to }
+    }
+
+    /// the first jump point when moving from `p` in direction `dir`: the goal, a cell with a
+    /// forced neighbor, or, when moving vertically, a cell from which a horizontal jump finds
+    /// one.
+    fn jump(&self, p: Pos, dir: Dir) -> Option<Pos> {
+        self.to[dir as usize - 1][p]
+    }
+
+    /// jump points reachable from `p`, which was entered moving in direction `dir`, together with
+    /// the direction of the jump.
+    fn successors(&self, p: Pos, dir: Dir) -> impl Iterator<Item = (Pos, Dir)> + '_ {
+        Dir::MOVES
+            .into_iter()
+            .filter(move |&d| match dir {
+                Dir::Start => true,
+                _ => d == dir || d.is_horizontal() != dir.is_horizontal(),
+            })
+            .filter_map(move |d| Some((self.jump(p, d)?, d)))
+    }
+}
+
+/// Reports the jump points of a search over `(Pos, Dir)` to an observer of positions.
+struct ByPos<O>(O);
+
+impl<O: SearchObserver<Pos>> SearchObserver<(Pos, Dir)> for ByPos<O> {
+    fn on_expand(&mut self, node: &(Pos, Dir), frontier_len: usize) {
+        self.0.on_expand(&node.0, frontier_len)
+    }
+
+    fn on_relax(&mut self, from: &(Pos, Dir), to: &(Pos, Dir)) {
+        self.0.on_relax(&from.0, &to.0)
+    }
+
+    fn on_finish(&mut self, found: bool) {
+        self.0.on_finish(found)
+    }
+}
+
+/// Jump Point Search on a 4-connected grid where every step costs 1: an
+/// [a_star_indexed](super::a_star_indexed) that skips over straight runs of open cells and only
+/// stops at jump points. Returns the full path, cell by cell, like an A* with a Manhattan
+/// heuristic would.
+pub fn jump_point_search<Cell>(
+    grid: &Grid<Cell>,
+    is_passable: impl Fn(Pos, &Cell) -> bool,
+    start: Pos,
+    goal: Pos,
+) -> Result<Path<Pos, ()>, NoPathFound> {
+    jump_point_search_observed(grid, is_passable, start, goal, ())
+}
+
+/// [jump_point_search], reporting its progress to `observer`, which only sees the jump points.
+pub fn jump_point_search_observed<Cell>(
+    grid: &Grid<Cell>,
+    is_passable: impl Fn(Pos, &Cell) -> bool,
+    start: Pos,
+    goal: Pos,
+    observer: impl SearchObserver<Pos>,
+) -> Result<Path<Pos, ()>, NoPathFound> {
+    let mut open = Grid::new(grid.size);
+    for p in grid.positions() {
+        open[p] = is_passable(p, &grid[p]);
+    }
+    if ![start, goal].iter().all(|&p| open.is_inside(p) && open[p]) {
+        return Err(NoPathFound);
+    }
+    let jumps = Jumps::new(&open, goal);
+    let jump_path = a_star_indexed_limited(
+        &(start, Dir::Start),
+        grid.size,
+        |&(p, _)| p == goal,
+        |&(p, dir)| jumps.successors(p, dir).map(|s| (s, ())),
+        |&(p, _)| Some(manhattan(p, goal)),
+        |&(a, _), _, &(b, _)| manhattan(a, b),
+        &SearchLimits::default(),
+        ByPos(observer),
+    )
+    .map_err(|_| NoPathFound)?;
+    let mut rev = Vec::new();
+    for (&(a, _), &(b, dir)) in jump_path.nodes().iter().zip(&jump_path.nodes()[1..]) {
+        let mut p = a;
+        while p != b {
+            rev.push((p, ()));
+            p = [p[0] + dir.vec()[0], p[1] + dir.vec()[1]];
+        }
+    }
+    rev.reverse();
+    Ok(Path::from_rev(rev, goal, |_, _, _| 1))
+}
+
+/// Plain [a_star](super::a_star) cell by cell with a Manhattan heuristic, on the same grids as
+/// [jump_point_search], to check it and other grid searches against.
+pub fn grid_a_star<Cell>(
+    grid: &Grid<Cell>,
+    is_passable: impl Fn(Pos, &Cell) -> bool,
+    start: Pos,
+    goal: Pos,
+    observer: impl SearchObserver<Pos>,
+) -> Result<Path<Pos, ()>, NoPathFound> {
+    let open = |p: Pos| grid.is_inside(p) && is_passable(p, &grid[p]);
+    if !open(start) {
+        return Err(NoPathFound);
+    }
+    a_star_observed(
+        &start,
+        |&p| p == goal,
+        |&[x, y]| {
+            [[x + 1, y], [x, y + 1], [x - 1, y], [x, y - 1]]
+                .into_iter()
+                .filter(|&p| open(p))
+                .map(|p| (p, ()))
+        },
+        |&p| manhattan(p, goal),
+        |_, _, _| 1,
+        observer,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::SearchStats;
+
+    #[test]
+    fn test_jump_point_search() {
+        let grid = Grid::from_lines(
+            [
+                "..........",
+                ".####.###.",
+                "....#...#.",
+                ".##.###.#.",
+                ".#......#.",
+                ".#.####.##",
+                "...#......",
+            ],
+            |_, c| c == '#',
+        );
+        let positions: Vec<_> = grid.positions().filter(|&p| !grid[p]).collect();
+        for &start in &positions {
+            for &goal in &positions {
+                let path = jump_point_search(&grid, |_, &wall| !wall, start, goal);
+                let expected = grid_a_star(&grid, |_, &wall| !wall, start, goal, ());
+                assert_eq!(
+                    path.as_ref().ok().map(Path::cost),
+                    expected.ok().map(|p| p.cost())
+                );
+                let path = path.unwrap();
+                assert_eq!((*path.start(), *path.goal()), (start, goal));
+                for w in path.nodes().windows(2) {
+                    assert_eq!(manhattan(w[0], w[1]), 1);
+                    assert!(!grid[w[1]]);
+                }
+            }
+        }
+        let walled = Grid::from_lines(["..#..", "..#.."], |_, c| c == '#');
+        assert!(jump_point_search(&walled, |_, &wall| !wall, [0, 0], [4, 1]).is_err());
+    }
+
+    #[test]
+    fn test_jump_point_search_expands_less() {
+        // an open 60x60 grid with a few walls.
+        let grid = Grid::new_with_pos([60, 60], |[x, y]| {
+            (x % 12 == 6 && y % 10 < 7) || (y == 30 && x % 15 > 3)
+        });
+        let (start, goal) = ([0, 0], [59, 59]);
+        let mut jps_stats = SearchStats::new();
+        let path = jump_point_search_observed(&grid, |_, &wall| !wall, start, goal, &mut jps_stats)
+            .unwrap();
+        let mut a_star_stats = SearchStats::new();
+        let expected =
+            grid_a_star(&grid, |_, &wall| !wall, start, goal, &mut a_star_stats).unwrap();
+        assert_eq!(path.cost(), expected.cost());
+        assert_eq!(jps_stats.found, Some(true));
+        assert!(
+            jps_stats.expanded * 2 < a_star_stats.expanded,
+            "{} vs {}",
+            jps_stats.expanded,
+            a_star_stats.expanded
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use super::{Cost, NoPathFound};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use std::{error, fmt};
+
+/// Shared flag to stop a running search from another thread (or from an observer).
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Bounds for a search. Every limit is off by default.
+#[derive(Clone, Debug)]
+pub struct SearchLimits<C = i64> {
+    /// stop after this many nodes were expanded.
+    pub max_expanded: Option<u64>,
+    /// ignore paths whose estimated total cost (cost so far plus heuristic) is above this. Has no
+    /// effect on searches without costs, like [bfs](super::bfs).
+    pub max_cost: Option<C>,
+    /// stop after this much time.
+    pub timeout: Option<Duration>,
+    pub cancel: Option<CancellationToken>,
+}
+
+/// Which of the [SearchLimits] stopped a search.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Limit {
+    Expanded,
+    Cost,
+    Time,
+    Cancelled,
+}
+
+impl<C> Default for SearchLimits<C> {
+    fn default() -> Self {
+        Self {
+            max_expanded: None,
+            max_cost: None,
+            timeout: None,
+            cancel: None,
+        }
+    }
+}
+
+impl<C> SearchLimits<C> {
+    /// the limit that has been reached, apart from [Limit::Cost] which the searches check
+    /// themselves.
+    pub(super) fn check(&self, expanded: u64, started: Instant) -> Option<Limit> {
+        if self.max_expanded.is_some_and(|m| expanded >= m) {
+            Some(Limit::Expanded)
+        } else if self.timeout.is_some_and(|t| started.elapsed() >= t) {
+            Some(Limit::Time)
+        } else if self.cancel.as_ref().is_some_and(|c| c.is_cancelled()) {
+            Some(Limit::Cancelled)
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn exceeds_cost(&self, estimate: C) -> bool
+    where
+        C: Cost,
+    {
+        self.max_cost.is_some_and(|m| estimate > m)
+    }
+}
+
+/// What a search knew when it hit one of its [SearchLimits].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SearchAborted<Node> {
+    pub limit: Limit,
+    /// the expanded node that seemed closest to a goal: lowest heuristic for
+    /// [a_star_rev_limited](super::a_star_rev_limited), the last one (furthest from the start)
+    /// for [bfs_limited](super::bfs_limited).
+    pub best: Node,
+    pub expanded: u64,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum SearchError<Node> {
+    NoPathFound,
+    Aborted(SearchAborted<Node>),
+}
+
+impl<Node> From<NoPathFound> for SearchError<Node> {
+    fn from(_: NoPathFound) -> Self {
+        Self::NoPathFound
+    }
+}
+
+impl<Node> fmt::Display for SearchAborted<Node> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "search aborted ({:?}) after expanding {} nodes",
+            self.limit, self.expanded
+        )
+    }
+}
+
+impl<Node> fmt::Display for SearchError<Node> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchError::NoPathFound => fmt::Display::fmt(&NoPathFound, f),
+            SearchError::Aborted(a) => fmt::Display::fmt(a, f),
+        }
+    }
+}
+
+impl<Node: Debug> error::Error for SearchAborted<Node> {}
+
+impl<Node: Debug> error::Error for SearchError<Node> {}
@@ -0,0 +1,232 @@
+use super::{Cost, NoPathFound, Path};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Everything reachable from `start`: the nodes, their outgoing edges by id, and the ids in
+/// topological order. Panics if there is a cycle.
+#[allow(clippy::type_complexity)]
+fn topological_order<Node, Edge, Neighbors>(
+    start: &Node,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+) -> (Vec<Node>, Vec<Vec<(usize, Edge)>>, Vec<usize>)
+where
+    Node: Clone + Eq + Hash,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+{
+    let mut ids = HashMap::from([(start.clone(), 0)]);
+    let mut nodes = vec![start.clone()];
+    let mut edges: Vec<Option<Vec<(usize, Edge)>>> = vec![None];
+    let mut finished = vec![false];
+    let mut on_stack = vec![true];
+    let mut postorder = Vec::new();
+    let mut stack = vec![(0, 0)];
+    while let Some(&mut (u, ref mut i)) = stack.last_mut() {
+        if edges[u].is_none() {
+            let mut out = Vec::new();
+            for (n, e) in get_neighbors(&nodes[u]) {
+                let id = *ids.entry(n).or_insert_with_key(|n| {
+                    nodes.push(n.clone());
+                    edges.push(None);
+                    finished.push(false);
+                    on_stack.push(false);
+                    nodes.len() - 1
+                });
+                out.push((id, e));
+            }
+            edges[u] = Some(out);
+        }
+        if let Some(&(v, _)) = edges[u].as_ref().unwrap().get(*i) {
+            *i += 1;
+            if finished[v] {
+                continue;
+            }
+            assert!(!on_stack[v], "graph has a cycle");
+            on_stack[v] = true;
+            stack.push((v, 0));
+            continue;
+        }
+        finished[u] = true;
+        on_stack[u] = false;
+        postorder.push(u);
+        stack.pop();
+    }
+    postorder.reverse();
+    (
+        nodes,
+        edges.into_iter().map(Option::unwrap).collect(),
+        postorder,
+    )
+}
+
+/// The most expensive path from `start` to a goal in a graph without cycles, found by relaxing
+/// the edges in topological order. Costs may be negative. Panics if a cycle is reachable from
+/// `start`.
+pub fn longest_dag_path<Node, Edge, Neighbors, C>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    distance: impl Fn(&Node, &Edge, &Node) -> C,
+) -> Result<Path<Node, Edge, C>, NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    C: Cost,
+{
+    let (nodes, edges, order) = topological_order(start, get_neighbors);
+    let mut best = vec![None; nodes.len()];
+    // the node and the index of the edge that led to each node.
+    let mut parent = vec![None; nodes.len()];
+    best[0] = Some(C::zero());
+    for &u in &order {
+        let Some(g) = best[u] else {
+            continue;
+        };
+        for (i, (v, e)) in edges[u].iter().enumerate() {
+            let cost = g.plus(distance(&nodes[u], e, &nodes[*v]));
+            if best[*v].is_none_or(|c| cost > c) {
+                best[*v] = Some(cost);
+                parent[*v] = Some((u, i));
+            }
+        }
+    }
+    let goal = (0..nodes.len())
+        .filter(|&v| is_goal(&nodes[v]))
+        .filter_map(|v| Some((best[v]?, v)))
+        .max_by_key(|&(c, _)| c)
+        .ok_or(NoPathFound)?
+        .1;
+    let mut rev = Vec::new();
+    let mut v = goal;
+    while let Some((u, i)) = parent[v] {
+        rev.push((nodes[u].clone(), edges[u][i].1.clone()));
+        v = u;
+    }
+    Ok(Path::from_rev(rev, nodes[goal].clone(), distance))
+}
+
+/// The most expensive path from `start` to a goal that visits no node twice, in a general graph,
+/// by trying every simple path with at most `max_depth` steps (unbounded if `None`). Exponential,
+/// so only for small graphs, like the junctions of a maze after
+/// [contract_corridors](super::contract_corridors).
+pub fn longest_simple_path<Node, Edge, Neighbors, C>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    distance: impl Fn(&Node, &Edge, &Node) -> C,
+    max_depth: Option<usize>,
+) -> Result<Path<Node, Edge, C>, NoPathFound>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    C: Cost,
+{
+    // the current path: every node, the edge it was entered by and its cost so far, and the
+    // neighbors still to try.
+    let mut stack = vec![(
+        start.clone(),
+        None::<Edge>,
+        C::zero(),
+        get_neighbors(start)
+            .into_iter()
+            .collect::<Vec<_>>()
+            .into_iter(),
+    )];
+    let mut on_path = HashSet::from([start.clone()]);
+    let mut best = is_goal(start).then(|| (C::zero(), Vec::new(), start.clone()));
+
+    while let Some((node, _, g, neighbors)) = stack.last_mut() {
+        let Some((next, edge)) = neighbors.next() else {
+            let (node, ..) = stack.pop().unwrap();
+            on_path.remove(&node);
+            continue;
+        };
+        if on_path.contains(&next) {
+            continue;
+        }
+        let cost = g.plus(distance(node, &edge, &next));
+        if is_goal(&next) && best.as_ref().is_none_or(|(c, ..)| cost > *c) {
+            let mut rev = vec![(node.clone(), edge.clone())];
+            for i in (1..stack.len()).rev() {
+                rev.push((stack[i - 1].0.clone(), stack[i].1.clone().unwrap()));
+            }
+            best = Some((cost, rev, next.clone()));
+        }
+        if max_depth.is_some_and(|d| stack.len() >= d) {
+            continue;
+        }
+        let next_neighbors = get_neighbors(&next).into_iter().collect::<Vec<_>>();
+        on_path.insert(next.clone());
+        stack.push((next, Some(edge), cost, next_neighbors.into_iter()));
+    }
+
+    let (_, rev, goal) = best.ok_or(NoPathFound)?;
+    Ok(Path::from_rev(rev, goal, distance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `(a, b, cost)`: a DAG with the longest path 0 -> 1 -> 2 -> 4 -> 5 of cost 16.
+    const DAG: [(u32, u32, i64); 8] = [
+        (0, 1, 5),
+        (0, 2, 3),
+        (1, 3, 6),
+        (1, 2, 2),
+        (2, 4, 4),
+        (2, 5, 2),
+        (3, 5, 1),
+        (4, 5, 5),
+    ];
+
+    fn neighbors(edges: &[(u32, u32, i64)], n: u32) -> Vec<(u32, i64)> {
+        edges
+            .iter()
+            .filter(|e| e.0 == n)
+            .map(|e| (e.1, e.2))
+            .collect()
+    }
+
+    #[test]
+    fn test_longest_dag_path() {
+        let path =
+            longest_dag_path(&0, |&n| n == 5, |&n| neighbors(&DAG, n), |_, &c, _| c).unwrap();
+        assert_eq!(path.nodes(), [0, 1, 2, 4, 5]);
+        assert_eq!(path.cost(), 16);
+        let simple =
+            longest_simple_path(&0, |&n| n == 5, |&n| neighbors(&DAG, n), |_, &c, _| c, None)
+                .unwrap();
+        assert_eq!(simple, path);
+        assert!(longest_dag_path(&3, |&n| n == 4, |&n| neighbors(&DAG, n), |_, &c, _| c).is_err());
+        // a long chain, which takes quadratic time if the cycle check scans the stack.
+        let n = 100_000u32;
+        let path = longest_dag_path(
+            &0,
+            |&a| a == n - 1,
+            |&a| (a + 1 < n).then_some((a + 1, ())),
+            |_, _, _| 1,
+        )
+        .unwrap();
+        assert_eq!(path.cost(), n as i64 - 1);
+    }
+
+    #[test]
+    fn test_longest_simple_path() {
+        // an undirected 3x3 grid, the longest simple path between two corners visits every cell.
+        let grid = |&[x, y]: &[i32; 2]| {
+            [[x + 1, y], [x, y + 1], [x - 1, y], [x, y - 1]]
+                .into_iter()
+                .filter(|&[x, y]| (0..3).contains(&x) && (0..3).contains(&y))
+                .map(|n| (n, ()))
+                .collect::<Vec<_>>()
+        };
+        let path = longest_simple_path(&[0, 0], |&n| n == [2, 2], grid, |_, _, _| 1, None).unwrap();
+        assert_eq!(path.cost(), 8);
+        assert_eq!(path.nodes().iter().collect::<HashSet<_>>().len(), 9);
+        let path =
+            longest_simple_path(&[0, 0], |&n| n == [2, 2], grid, |_, _, _| 1, Some(6)).unwrap();
+        assert_eq!(path.cost(), 6);
+    }
+}
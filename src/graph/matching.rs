@@ -0,0 +1,222 @@
+use std::collections::VecDeque;
+
+/// Result of [hungarian].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Assignment {
+    pub cost: i64,
+    /// `(row, column)` for every row, sorted by row.
+    pub pairs: Vec<(usize, usize)>,
+}
+
+/// Hopcroft–Karp: a maximum matching between the nodes `0..left` and `0..right`, where `edges`
+/// are `(left node, right node)` pairs. Returns the matched pairs, sorted by left node.
+pub fn hopcroft_karp(
+    left: usize,
+    right: usize,
+    edges: impl IntoIterator<Item = (usize, usize)>,
+) -> Vec<(usize, usize)> {
+    let mut adjacency = vec![Vec::new(); left];
+    for (u, v) in edges {
+        assert!(v < right);
+        adjacency[u].push(v);
+    }
+    let mut matching = Matching {
+        adjacency,
+        left: vec![None; left],
+        right: vec![None; right],
+        dist: vec![usize::MAX; left],
+        next: vec![0; left],
+    };
+    while matching.layer() {
+        matching.next.fill(0);
+        for u in 0..left {
+            if matching.left[u].is_none() {
+                matching.augment(u);
+            }
+        }
+    }
+    (0..left)
+        .filter_map(|u| Some((u, matching.left[u]?)))
+        .collect()
+}
+
+struct Matching {
+    adjacency: Vec<Vec<usize>>,
+    /// the partner of every node on either side.
+    left: Vec<Option<usize>>,
+    right: Vec<Option<usize>>,
+    /// alternating path length from a free left node, `usize::MAX` if unreached.
+    dist: Vec<usize>,
+    /// the next edge to try for every left node.
+    next: Vec<usize>,
+}
+
+impl Matching {
+    /// BFS from every free left node. Returns whether a free right node can be reached.
+    fn layer(&mut self) -> bool {
+        let mut queue = VecDeque::new();
+        for u in 0..self.left.len() {
+            self.dist[u] = if self.left[u].is_none() {
+                queue.push_back(u);
+                0
+            } else {
+                usize::MAX
+            };
+        }
+        let mut found = false;
+        while let Some(u) = queue.pop_front() {
+            for &v in &self.adjacency[u] {
+                match self.right[v] {
+                    None => found = true,
+                    Some(w) if self.dist[w] == usize::MAX => {
+                        self.dist[w] = self.dist[u] + 1;
+                        queue.push_back(w);
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        found
+    }
+
+    /// DFS along the layers for a shortest augmenting path from `u`, flipping it if found.
+    fn augment(&mut self, u: usize) -> bool {
+        while let Some(&v) = self.adjacency[u].get(self.next[u]) {
+            self.next[u] += 1;
+            let free = match self.right[v] {
+                None => true,
+                Some(w) => self.dist[w] == self.dist[u] + 1 && self.augment(w),
+            };
+            if free {
+                self.left[u] = Some(v);
+                self.right[v] = Some(u);
+                return true;
+            }
+        }
+        self.dist[u] = usize::MAX;
+        false
+    }
+}
+
+/// Hungarian algorithm: assigns every row of `costs` a different column so that the total cost
+/// is minimal. All rows must have the same length, at least the number of rows.
+pub fn hungarian(costs: &[Vec<i64>]) -> Assignment {
+    const INF: i64 = i64::MAX / 4;
+    let n = costs.len();
+    let m = costs.first().map_or(0, Vec::len);
+    assert!(n <= m && costs.iter().all(|row| row.len() == m));
+    // potentials of rows and columns, and the row assigned to every column, all 1-based so that
+    // column 0 can hold the row being added.
+    let mut u = vec![0; n + 1];
+    let mut v = vec![0; m + 1];
+    let mut row_of = vec![0; m + 1];
+    let mut way = vec![0; m + 1];
+    for i in 1..=n {
+        row_of[0] = i;
+        let mut j0 = 0;
+        let mut min = vec![INF; m + 1];
+        let mut used = vec![false; m + 1];
+        while row_of[j0] != 0 {
+            used[j0] = true;
+            let i0 = row_of[j0];
+            let mut delta = INF;
+            let mut j1 = 0;
+            for j in 1..=m {
+                if used[j] {
+                    continue;
+                }
+                let reduced = costs[i0 - 1][j - 1] - u[i0] - v[j];
+                if reduced < min[j] {
+                    min[j] = reduced;
+                    way[j] = j0;
+                }
+                if min[j] < delta {
+                    delta = min[j];
+                    j1 = j;
+                }
+            }
+            for j in 0..=m {
+                if used[j] {
+                    u[row_of[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min[j] -= delta;
+                }
+            }
+            j0 = j1;
+        }
+        while j0 != 0 {
+            let j1 = way[j0];
+            row_of[j0] = row_of[j1];
+            j0 = j1;
+        }
+    }
+    let mut pairs: Vec<_> = (1..=m)
+        .filter(|&j| row_of[j] != 0)
+        .map(|j| (row_of[j] - 1, j - 1))
+        .collect();
+    pairs.sort_unstable();
+    Assignment {
+        cost: pairs.iter().map(|&(i, j)| costs[i][j]).sum(),
+        pairs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::FlowNetwork;
+    use itertools::Itertools;
+
+    #[test]
+    fn test_hopcroft_karp() {
+        // pseudo random graphs, checked against the maximum flow.
+        for seed in 0..20u64 {
+            let (left, right) = (7 + seed as usize % 3, 6 + seed as usize % 4);
+            let edges: Vec<_> = (0..left)
+                .cartesian_product(0..right)
+                .filter(|&(u, v)| (u as u64 * 31 + v as u64 * 17 + seed * 7).is_multiple_of(5))
+                .collect();
+            let matching = hopcroft_karp(left, right, edges.iter().copied());
+            assert!(matching.iter().all(|e| edges.contains(e)));
+            assert!(matching.iter().map(|e| e.1).all_unique());
+            let mut network = FlowNetwork::new(left + right + 2);
+            let (source, sink) = (left + right, left + right + 1);
+            for &(u, v) in &edges {
+                network.add_edge(u, left + v, 1);
+            }
+            (0..left).for_each(|u| _ = network.add_edge(source, u, 1));
+            (0..right).for_each(|v| _ = network.add_edge(left + v, sink, 1));
+            assert_eq!(matching.len() as i64, network.max_flow(source, sink).value);
+        }
+    }
+
+    #[test]
+    fn test_hungarian() {
+        let costs = vec![vec![4, 1, 3], vec![2, 0, 5], vec![3, 2, 2]];
+        assert_eq!(
+            hungarian(&costs),
+            Assignment {
+                cost: 5,
+                pairs: vec![(0, 1), (1, 0), (2, 2)],
+            }
+        );
+        // more columns than rows, checked against trying every assignment.
+        let costs: Vec<Vec<i64>> = (0..4)
+            .map(|i| (0..6).map(|j| (i * 7 + j * 13) % 11 - 3).collect())
+            .collect();
+        let best = (0..6)
+            .permutations(4)
+            .map(|cols| {
+                cols.iter()
+                    .enumerate()
+                    .map(|(i, &j)| costs[i][j])
+                    .sum::<i64>()
+            })
+            .min()
+            .unwrap();
+        let assignment = hungarian(&costs);
+        assert_eq!(assignment.cost, best);
+        assert!(assignment.pairs.iter().map(|p| p.1).all_unique());
+    }
+}
@@ -0,0 +1,121 @@
+use indicatif::ProgressBar;
+use std::time::{Duration, Instant};
+
+/// Hooks called by the searches. All of them do nothing by default, `()` ignores everything.
+pub trait SearchObserver<Node> {
+    /// `node` was taken from the frontier, which now holds `frontier_len` nodes.
+    fn on_expand(&mut self, _node: &Node, _frontier_len: usize) {}
+
+    /// a (cheaper) way to `to` through `from` was found.
+    fn on_relax(&mut self, _from: &Node, _to: &Node) {}
+
+    /// an iterative deepening search finished its `iteration`-th pass (counting from 0), which
+    /// expanded `expanded` nodes.
+    fn on_iteration(&mut self, _iteration: usize, _expanded: u64) {}
+
+    /// the search ended, successfully or not.
+    fn on_finish(&mut self, _found: bool) {}
+}
+
+impl<Node> SearchObserver<Node> for () {}
+
+impl<Node, O: SearchObserver<Node>> SearchObserver<Node> for &mut O {
+    fn on_expand(&mut self, node: &Node, frontier_len: usize) {
+        (**self).on_expand(node, frontier_len)
+    }
+
+    fn on_relax(&mut self, from: &Node, to: &Node) {
+        (**self).on_relax(from, to)
+    }
+
+    fn on_iteration(&mut self, iteration: usize, expanded: u64) {
+        (**self).on_iteration(iteration, expanded)
+    }
+
+    fn on_finish(&mut self, found: bool) {
+        (**self).on_finish(found)
+    }
+}
+
+/// [SearchObserver] that counts what the search did, optionally showing it on a progress bar.
+#[derive(Clone, Debug)]
+pub struct SearchStats {
+    pub expanded: u64,
+    pub relaxed: u64,
+    pub max_frontier: usize,
+    /// nodes expanded by each pass of an iterative deepening search.
+    pub iterations: Vec<u64>,
+    pub found: Option<bool>,
+    started: Instant,
+    finished: Option<Instant>,
+    progress: Option<ProgressBar>,
+}
+
+impl Default for SearchStats {
+    fn default() -> Self {
+        Self {
+            expanded: 0,
+            relaxed: 0,
+            max_frontier: 0,
+            iterations: Vec::new(),
+            found: None,
+            started: Instant::now(),
+            finished: None,
+            progress: None,
+        }
+    }
+}
+
+impl SearchStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advances `progress` by one per expanded node and shows the frontier size as its message.
+    pub fn with_progress(progress: ProgressBar) -> Self {
+        Self {
+            progress: Some(progress),
+            ..Self::default()
+        }
+    }
+
+    /// time from creation until the search finished, or until now if it is still running.
+    pub fn elapsed(&self) -> Duration {
+        self.finished.unwrap_or_else(Instant::now) - self.started
+    }
+}
+
+impl<Node> SearchObserver<Node> for SearchStats {
+    fn on_expand(&mut self, _node: &Node, frontier_len: usize) {
+        self.expanded += 1;
+        self.max_frontier = self.max_frontier.max(frontier_len);
+        if let Some(ref pb) = self.progress {
+            pb.inc(1);
+            if self.expanded.is_multiple_of(1024) {
+                pb.set_message(format!("frontier={}", frontier_len));
+            }
+        }
+    }
+
+    fn on_relax(&mut self, _from: &Node, _to: &Node) {
+        self.relaxed += 1;
+    }
+
+    fn on_iteration(&mut self, iteration: usize, expanded: u64) {
+        self.iterations.push(expanded);
+        if let Some(ref pb) = self.progress {
+            pb.set_message(format!("iteration={}, expanded={}", iteration, expanded));
+        }
+    }
+
+    fn on_finish(&mut self, found: bool) {
+        self.found = Some(found);
+        self.finished = Some(Instant::now());
+        if let Some(ref pb) = self.progress {
+            pb.finish_with_message(format!(
+                "expanded={}, max frontier={}",
+                self.expanded, self.max_frontier
+            ));
+        }
+    }
+}
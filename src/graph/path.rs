@@ -0,0 +1,136 @@
+use super::Cost;
+
+/// A path found by one of the searches, in forward order from the start to the goal.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Path<Node, Edge, C = i64> {
+    pub(super) nodes: Vec<Node>,
+    /// `edges[i]` leads from `nodes[i]` to `nodes[i + 1]`.
+    pub(super) edges: Vec<Edge>,
+    /// `costs[i]` is the cost of reaching `nodes[i]`.
+    pub(super) costs: Vec<C>,
+}
+
+impl<Node, Edge, C: Cost> Path<Node, Edge, C> {
+    /// Builds the path from the result of one of the `_rev` searches, like
+    /// [a_star_rev](super::a_star_rev), using `distance` for the cost of every step.
+    pub fn from_rev(
+        rev: Vec<(Node, Edge)>,
+        goal: Node,
+        distance: impl Fn(&Node, &Edge, &Node) -> C,
+    ) -> Self {
+        Self::from_rev_with_base(rev, goal, C::zero(), distance)
+    }
+
+    pub(super) fn from_rev_with_base(
+        rev: Vec<(Node, Edge)>,
+        goal: Node,
+        base: C,
+        distance: impl Fn(&Node, &Edge, &Node) -> C,
+    ) -> Self {
+        let mut nodes = Vec::with_capacity(rev.len() + 1);
+        let mut edges = Vec::with_capacity(rev.len());
+        for (n, e) in rev.into_iter().rev() {
+            nodes.push(n);
+            edges.push(e);
+        }
+        nodes.push(goal);
+        let mut costs = Vec::with_capacity(nodes.len());
+        costs.push(base);
+        for (i, e) in edges.iter().enumerate() {
+            costs.push(costs[i].plus(distance(&nodes[i], e, &nodes[i + 1])));
+        }
+        Self {
+            nodes,
+            edges,
+            costs,
+        }
+    }
+
+    /// cost of the whole path.
+    pub fn cost(&self) -> C {
+        *self.costs.last().unwrap()
+    }
+}
+
+impl<Node, Edge, C> Path<Node, Edge, C> {
+    /// every node from the start to the goal, both included.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// the edges between consecutive [nodes](Self::nodes).
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// cost of reaching each of the [nodes](Self::nodes), starting with zero.
+    pub fn costs(&self) -> &[C] {
+        &self.costs
+    }
+
+    pub fn start(&self) -> &Node {
+        &self.nodes[0]
+    }
+
+    pub fn goal(&self) -> &Node {
+        self.nodes.last().unwrap()
+    }
+
+    /// number of steps, which is one less than the number of nodes.
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// whether the start is the goal.
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// every step as `(from, edge, to, cost of reaching to)`.
+    pub fn steps(&self) -> impl Iterator<Item = (&Node, &Edge, &Node, &C)> {
+        self.nodes
+            .windows(2)
+            .zip(&self.edges)
+            .zip(&self.costs[1..])
+            .map(|((w, e), c)| (&w[0], e, &w[1], c))
+    }
+
+    pub fn into_nodes(self) -> Vec<Node> {
+        self.nodes
+    }
+
+    /// the path as returned by [a_star_rev](super::a_star_rev): every node except the goal
+    /// together with the edge leaving it, in reverse order, and the goal.
+    pub fn into_rev(self) -> (Vec<(Node, Edge)>, Node) {
+        let mut nodes = self.nodes;
+        let goal = nodes.pop().unwrap();
+        (nodes.into_iter().zip(self.edges).rev().collect(), goal)
+    }
+}
+
+impl<Node, Edge, C> From<Path<Node, Edge, C>> for Vec<Node> {
+    fn from(path: Path<Node, Edge, C>) -> Self {
+        path.into_nodes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path() {
+        let rev = vec![(2, 'c'), (1, 'b'), (0, 'a')];
+        let path = Path::from_rev(rev.clone(), 3, |&a, _, &b| (b - a) * 10);
+        assert_eq!(path.nodes(), [0, 1, 2, 3]);
+        assert_eq!(path.edges(), ['a', 'b', 'c']);
+        assert_eq!(path.costs(), [0, 10, 20, 30]);
+        assert_eq!(
+            (path.cost(), path.len(), *path.start(), *path.goal()),
+            (30, 3, 0, 3)
+        );
+        assert_eq!(path.steps().nth(1), Some((&1, &'b', &2, &20)));
+        assert_eq!(path.clone().into_rev(), (rev, 3));
+        assert_eq!(Vec::from(path), [0, 1, 2, 3]);
+    }
+}
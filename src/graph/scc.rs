@@ -0,0 +1,266 @@
+use super::{Graph, NodeId};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+const UNVISITED: usize = usize::MAX;
+
+/// Tarjan's algorithm on dense ids, with an explicit call stack so that long paths do not
+/// overflow the stack. Ids may be discovered while running, the tables grow as needed.
+#[derive(Default)]
+struct Tarjan {
+    index: Vec<usize>,
+    low: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    component: Vec<usize>,
+    next_index: usize,
+    count: usize,
+}
+
+impl Tarjan {
+    fn ensure(&mut self, v: usize) {
+        if v >= self.index.len() {
+            self.index.resize(v + 1, UNVISITED);
+            self.low.resize(v + 1, UNVISITED);
+            self.on_stack.resize(v + 1, false);
+            self.component.resize(v + 1, UNVISITED);
+        }
+    }
+
+    fn run(&mut self, root: usize, neighbors: &mut impl FnMut(usize) -> Vec<usize>) {
+        self.ensure(root);
+        if self.index[root] != UNVISITED {
+            return;
+        }
+        let mut calls = Vec::new();
+        self.visit(root, neighbors, &mut calls);
+        while let Some((v, ns, i)) = calls.last_mut() {
+            let v = *v;
+            if let Some(&w) = ns.get(*i) {
+                *i += 1;
+                self.ensure(w);
+                if self.index[w] == UNVISITED {
+                    self.visit(w, neighbors, &mut calls);
+                } else if self.on_stack[w] {
+                    self.low[v] = self.low[v].min(self.index[w]);
+                }
+                continue;
+            }
+            calls.pop();
+            if let Some(&(parent, _, _)) = calls.last() {
+                self.low[parent] = self.low[parent].min(self.low[v]);
+            }
+            if self.low[v] == self.index[v] {
+                loop {
+                    let w = self.stack.pop().unwrap();
+                    self.on_stack[w] = false;
+                    self.component[w] = self.count;
+                    if w == v {
+                        break;
+                    }
+                }
+                self.count += 1;
+            }
+        }
+    }
+
+    fn visit(
+        &mut self,
+        v: usize,
+        neighbors: &mut impl FnMut(usize) -> Vec<usize>,
+        calls: &mut Vec<(usize, Vec<usize>, usize)>,
+    ) {
+        self.index[v] = self.next_index;
+        self.low[v] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(v);
+        self.on_stack[v] = true;
+        calls.push((v, neighbors(v), 0));
+    }
+
+    /// component of every id, numbered so that edges only lead to components with a higher id.
+    fn into_topological(self) -> (Vec<usize>, usize) {
+        let count = self.count;
+        (
+            self.component.into_iter().map(|c| count - 1 - c).collect(),
+            count,
+        )
+    }
+}
+
+fn condense<Node: Clone>(
+    nodes: &[Node],
+    component: &[usize],
+    count: usize,
+    mut neighbors: impl FnMut(usize) -> Vec<usize>,
+) -> Graph<Vec<Node>, ()> {
+    let mut dag = Graph::from_edges(vec![Vec::new(); count], []);
+    for (v, &c) in component.iter().enumerate() {
+        dag.nodes[c].push(nodes[v].clone());
+    }
+    let mut edges = HashSet::new();
+    for (v, &c) in component.iter().enumerate() {
+        for w in neighbors(v) {
+            let d = component[w];
+            if c != d && edges.insert((c, d)) {
+                dag.add_edge(c, d, ());
+            }
+        }
+    }
+    dag
+}
+
+/// Strongly connected components of everything reachable from `roots`. Components are numbered
+/// in topological order, i.e. every edge between two components leads to the higher id.
+pub fn strongly_connected_components<Node, Neighbors>(
+    roots: impl IntoIterator<Item = Node>,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+) -> (HashMap<Node, usize>, usize)
+where
+    Node: Clone + Eq + Hash,
+    Neighbors: IntoIterator<Item = Node>,
+{
+    let (nodes, component, count, _) = intern_and_run(roots, get_neighbors);
+    (nodes.into_iter().zip(component).collect(), count)
+}
+
+/// Condensation of everything reachable from `roots`: one node per strongly connected component
+/// holding its members, numbered as in [strongly_connected_components], so `0..len` is a
+/// topological order of the DAG. Also returns the component of every node.
+pub fn condensation<Node, Neighbors>(
+    roots: impl IntoIterator<Item = Node>,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+) -> (HashMap<Node, usize>, Graph<Vec<Node>, ()>)
+where
+    Node: Clone + Eq + Hash,
+    Neighbors: IntoIterator<Item = Node>,
+{
+    let (nodes, component, count, edges) = intern_and_run(roots, get_neighbors);
+    let dag = condense(&nodes, &component, count, |v| edges[v].clone());
+    (nodes.into_iter().zip(component).collect(), dag)
+}
+
+fn intern_and_run<Node, Neighbors>(
+    roots: impl IntoIterator<Item = Node>,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+) -> (Vec<Node>, Vec<usize>, usize, Vec<Vec<usize>>)
+where
+    Node: Clone + Eq + Hash,
+    Neighbors: IntoIterator<Item = Node>,
+{
+    let mut ids = HashMap::new();
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    let mut intern = |n: Node, nodes: &mut Vec<Node>| {
+        *ids.entry(n).or_insert_with_key(|n| {
+            nodes.push(n.clone());
+            nodes.len() - 1
+        })
+    };
+    let roots: Vec<_> = roots.into_iter().map(|n| intern(n, &mut nodes)).collect();
+    let mut tarjan = Tarjan::default();
+    let mut neighbors = |v: usize| {
+        let node = nodes[v].clone();
+        let ns: Vec<_> = get_neighbors(&node)
+            .into_iter()
+            .map(|n| intern(n, &mut nodes))
+            .collect();
+        if edges.len() <= v {
+            edges.resize(v + 1, Vec::new());
+        }
+        edges[v] = ns.clone();
+        ns
+    };
+    for r in roots {
+        tarjan.run(r, &mut neighbors);
+    }
+    let (component, count) = tarjan.into_topological();
+    edges.resize(nodes.len(), Vec::new());
+    (nodes, component, count, edges)
+}
+
+impl<N, E> Graph<N, E> {
+    fn neighbor_ids(&self, v: NodeId) -> Vec<NodeId> {
+        self.neighbors(v).map(|(w, _)| w).collect()
+    }
+
+    /// Component of every node, numbered in topological order as in
+    /// [strongly_connected_components](super::strongly_connected_components), and the number of
+    /// components.
+    pub fn strongly_connected_components(&self) -> (Vec<usize>, usize) {
+        let mut tarjan = Tarjan::default();
+        if !self.is_empty() {
+            tarjan.ensure(self.len() - 1);
+        }
+        for v in self.node_ids() {
+            tarjan.run(v, &mut |v| self.neighbor_ids(v));
+        }
+        tarjan.into_topological()
+    }
+
+    /// [condensation](super::condensation) of the whole graph, with the ids of the members as
+    /// node weights.
+    pub fn condensation(&self) -> (Vec<usize>, Graph<Vec<NodeId>, ()>) {
+        let (component, count) = self.strongly_connected_components();
+        let ids: Vec<_> = self.node_ids().collect();
+        let dag = condense(&ids, &component, count, |v| self.neighbor_ids(v));
+        (component, dag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_condensation() {
+        let g = Graph::from_edges(
+            0..8,
+            [
+                (0, 1),
+                (1, 2),
+                (2, 0),
+                (3, 1),
+                (3, 2),
+                (3, 4),
+                (4, 3),
+                (4, 5),
+                (5, 2),
+                (5, 6),
+                (6, 5),
+                (7, 4),
+                (7, 6),
+                (7, 7),
+            ]
+            .map(|(a, b)| (a, b, ())),
+        );
+        let (component, dag) = g.condensation();
+        assert_eq!(component, [3, 3, 3, 1, 1, 2, 2, 0]);
+        assert_eq!(dag.nodes, [vec![7], vec![3, 4], vec![5, 6], vec![0, 1, 2]]);
+        for c in dag.node_ids() {
+            assert!(dag.neighbors(c).all(|(d, _)| d > c));
+        }
+        assert_eq!(dag.edge_count(), 5);
+
+        let (component, dag) = Graph::<u32, ()>::new().condensation();
+        assert!(component.is_empty());
+        assert!(dag.is_empty());
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        // a chain long enough to overflow the stack of a recursive implementation.
+        let n = 100_000u32;
+        let (component, count) = strongly_connected_components([0], |&a| {
+            (a + 1 < n)
+                .then_some(a + 1)
+                .into_iter()
+                .chain((a % 1000 == 999).then(|| a - 999))
+        });
+        assert_eq!(count, 100);
+        assert_eq!(component[&0], 0);
+        assert_eq!(component[&999], 0);
+        assert_eq!(component[&1000], 1);
+        assert_eq!(component[&(n - 1)], 99);
+    }
+}
@@ -0,0 +1,410 @@
+use std::mem;
+
+/// Order in which a route visits the nodes, and its cost. For round trips the order starts with
+/// the first node and does not repeat it at the end, so it looks the same as an open path from
+/// [hamiltonian_path]; only the cost tells them apart.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Route {
+    pub order: Vec<u16>,
+    pub cost: i64,
+}
+
+impl Route {
+    fn new(order: Vec<u16>, cost: i64) -> Self {
+        Self { order, cost }
+    }
+}
+
+/// Held-Karp table of the cheapest paths that leave `start` and visit a subset of the other
+/// nodes. The other nodes are numbered by their position in `others`, which is also their bit in
+/// the subset.
+struct HeldKarp {
+    start: u16,
+    others: Vec<u16>,
+    cost: Vec<i64>,
+    parent: Vec<u16>,
+}
+
+impl HeldKarp {
+    /// The table has `2^(n-1) * (n-1)` entries, so this is only feasible for about 25 nodes.
+    fn new(n: u16, start: u16, dist: &impl Fn(u16, u16) -> i64) -> Self {
+        assert!(start < n);
+        let others: Vec<u16> = (0..n).filter(|&k| k != start).collect();
+        let m = others.len();
+        let full = 1usize << m;
+        let mut cost = vec![i64::MAX; full * m];
+        let mut parent = vec![u16::MAX; full * m];
+        for k in 0..m {
+            cost[(1 << k) * m + k] = dist(start, others[k]);
+        }
+
+        for sub in 1..full {
+            for k in (0..m).filter(|k| sub & (1 << k) != 0) {
+                let c = cost[sub * m + k];
+                if c == i64::MAX {
+                    continue;
+                }
+                for j in (0..m).filter(|j| sub & (1 << j) == 0) {
+                    let i = (sub | (1 << j)) * m + j;
+                    let next = c + dist(others[k], others[j]);
+                    if next < cost[i] {
+                        cost[i] = next;
+                        parent[i] = k as u16;
+                    }
+                }
+            }
+        }
+
+        Self {
+            start,
+            others,
+            cost,
+            parent,
+        }
+    }
+
+    fn full(&self) -> usize {
+        (1 << self.others.len()) - 1
+    }
+
+    /// cost of visiting every node, ending at `others[k]`.
+    fn cost(&self, k: usize) -> i64 {
+        self.cost[self.full() * self.others.len() + k]
+    }
+
+    /// order of visiting every node, ending at `others[k]`.
+    fn path(&self, mut k: usize) -> Vec<u16> {
+        let m = self.others.len();
+        let mut path = Vec::with_capacity(m + 1);
+        let mut sub = self.full();
+        while sub != 0 {
+            path.push(self.others[k]);
+            let prev = self.parent[sub * m + k];
+            sub &= !(1 << k);
+            k = prev as usize;
+        }
+        path.push(self.start);
+        path.reverse();
+        path
+    }
+
+    /// cheapest way to visit every node and end up at `others[k]`, with `extra(k)` added.
+    fn best(&self, extra: impl Fn(usize) -> i64) -> Route {
+        let (k, total) = (0..self.others.len())
+            .map(|k| (k, self.cost(k) + extra(k)))
+            .min_by_key(|&(_, c)| c)
+            .unwrap();
+        Route::new(self.path(k), total)
+    }
+}
+
+/// Held-Karp over the nodes `0..n`. Returns the cheapest round trip starting and ending at node 0
+/// as the order in which the nodes are visited (starting with 0, without returning to it), and
+/// its cost.
+///
+/// Only feasible for about 25 nodes.
+pub fn tsp(n: u16, dist: impl Fn(u16, u16) -> i64) -> Route {
+    if n <= 1 {
+        return Route::new((0..n).collect(), 0);
+    }
+    let hk = HeldKarp::new(n, 0, &dist);
+    hk.best(|k| dist(hk.others[k], 0))
+}
+
+/// [tsp], but maximizing the cost.
+pub fn inv_tsp(n: u16, dist: impl Fn(u16, u16) -> i64) -> Route {
+    let Route { order, cost } = tsp(n, |a, b| -dist(a, b));
+    Route::new(order, -cost)
+}
+
+/// Cheapest path that starts at `start` and visits each of the nodes `0..n` exactly once, ending
+/// anywhere.
+pub fn hamiltonian_path(n: u16, start: u16, dist: impl Fn(u16, u16) -> i64) -> Route {
+    if n == 1 {
+        assert_eq!(start, 0);
+        return Route::new(vec![0], 0);
+    }
+    HeldKarp::new(n, start, &dist).best(|_| 0)
+}
+
+/// [hamiltonian_path] that has to end at `end`, which must differ from `start` unless `n == 1`.
+pub fn hamiltonian_path_to(n: u16, start: u16, end: u16, dist: impl Fn(u16, u16) -> i64) -> Route {
+    if n == 1 {
+        assert_eq!((start, end), (0, 0));
+        return Route::new(vec![0], 0);
+    }
+    assert_ne!(start, end);
+    let hk = HeldKarp::new(n, start, &dist);
+    let k = hk.others.iter().position(|&o| o == end).unwrap();
+    Route::new(hk.path(k), hk.cost(k))
+}
+
+/// SplitMix64, so that the heuristics are reproducible without pulling in a random crate.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+/// Distance matrix, so the local search does not call the distance function in its inner loops.
+struct Dist {
+    n: usize,
+    d: Vec<i64>,
+}
+
+impl Dist {
+    fn new(n: u16, dist: impl Fn(u16, u16) -> i64) -> Self {
+        let d = (0..n)
+            .flat_map(|a| (0..n).map(move |b| (a, b)))
+            .map(|(a, b)| dist(a, b));
+        Self {
+            n: n as usize,
+            d: d.collect(),
+        }
+    }
+
+    #[inline(always)]
+    fn get(&self, a: u16, b: u16) -> i64 {
+        self.d[a as usize * self.n + b as usize]
+    }
+
+    fn tour_cost(&self, order: &[u16]) -> i64 {
+        order
+            .iter()
+            .zip(order.iter().cycle().skip(1))
+            .map(|(&a, &b)| self.get(a, b))
+            .sum()
+    }
+
+    fn route(&self, mut order: Vec<u16>) -> Route {
+        if let Some(i) = order.iter().position(|&k| k == 0) {
+            order.rotate_left(i);
+        }
+        let cost = self.tour_cost(&order);
+        Route::new(order, cost)
+    }
+}
+
+fn nearest_neighbor_order(d: &Dist) -> Vec<u16> {
+    let n = d.n as u16;
+    let mut visited = vec![false; d.n];
+    let mut order = Vec::with_capacity(d.n);
+    let mut current = 0;
+    for _ in 0..n {
+        visited[current as usize] = true;
+        order.push(current);
+        if let Some(next) = (0..n)
+            .filter(|&k| !visited[k as usize])
+            .min_by_key(|&k| d.get(current, k))
+        {
+            current = next;
+        }
+    }
+    order
+}
+
+/// Reverses a segment whenever that shortens the tour. Assumes a symmetric distance.
+fn two_opt(d: &Dist, order: &mut [u16]) -> bool {
+    let n = order.len();
+    let mut improved = false;
+    for i in 0..n.saturating_sub(2) {
+        for j in i + 2..n {
+            if i == 0 && j == n - 1 {
+                continue;
+            }
+            let (a, b) = (order[i], order[i + 1]);
+            let (c, e) = (order[j], order[(j + 1) % n]);
+            if d.get(a, c) + d.get(b, e) < d.get(a, b) + d.get(c, e) {
+                order[i + 1..=j].reverse();
+                improved = true;
+            }
+        }
+    }
+    improved
+}
+
+/// Moves segments of up to three nodes to a cheaper place in the tour, keeping their direction.
+fn or_opt(d: &Dist, order: &mut Vec<u16>) -> bool {
+    let n = order.len();
+    let mut improved = false;
+    for len in 1..=3.min(n.saturating_sub(2)) {
+        let mut i = 1;
+        while i + len <= n {
+            let (first, last) = (order[i], order[i + len - 1]);
+            let (prev, next) = (order[i - 1], order[(i + len) % n]);
+            let gain = d.get(prev, first) + d.get(last, next) - d.get(prev, next);
+            let best = (0..n)
+                .filter(|&j| j + 1 < i || j >= i + len)
+                .map(|j| {
+                    let (a, b) = (order[j], order[(j + 1) % n]);
+                    (j, d.get(a, first) + d.get(last, b) - d.get(a, b))
+                })
+                .min_by_key(|&(_, cost)| cost);
+            if let Some((j, cost)) = best
+                && cost < gain
+            {
+                let segment: Vec<_> = order.drain(i..i + len).collect();
+                let at = if j < i { j + 1 } else { j + 1 - len };
+                order.splice(at..at, segment);
+                improved = true;
+            }
+            i += 1;
+        }
+    }
+    improved
+}
+
+fn local_search(d: &Dist, order: &mut Vec<u16>) {
+    while two_opt(d, order) | or_opt(d, order) {}
+}
+
+/// Double bridge: cuts the tour into `A B C D` and reconnects it as `A C B D`, which a single
+/// 2-opt or Or-opt move can not undo.
+fn double_bridge(order: &[u16], rng: &mut Rng) -> Vec<u16> {
+    let n = order.len();
+    let mut cuts = [
+        1 + rng.below(n - 1),
+        1 + rng.below(n - 1),
+        1 + rng.below(n - 1),
+    ];
+    cuts.sort();
+    let [p1, p2, p3] = cuts;
+    [&order[..p1], &order[p2..p3], &order[p1..p2], &order[p3..]].concat()
+}
+
+/// Nearest-neighbour round trip starting at node 0.
+pub fn tsp_nearest_neighbor(n: u16, dist: impl Fn(u16, u16) -> i64) -> Route {
+    let d = Dist::new(n, dist);
+    d.route(nearest_neighbor_order(&d))
+}
+
+/// Improves a round trip with 2-opt and Or-opt moves until neither finds an improvement. 2-opt
+/// reverses parts of the tour, so this assumes `dist` is symmetric.
+///
+/// `route` must be a round trip, like those of [tsp] or [tsp_heuristic]. The order of an open
+/// path from [hamiltonian_path] is taken as a round trip as well: the result is optimized for,
+/// and its cost includes, the way back to the first node.
+pub fn tsp_improve(route: Route, dist: impl Fn(u16, u16) -> i64) -> Route {
+    let d = Dist::new(route.order.len() as u16, dist);
+    let mut order = route.order;
+    local_search(&d, &mut order);
+    d.route(order)
+}
+
+/// Approximate [tsp] for instances too large for Held-Karp: nearest-neighbour construction and
+/// local search, followed by `kicks` rounds of iterated local search (random double bridge, then
+/// local search again), keeping the best tour. The kicks are drawn from `seed`, so the result is
+/// reproducible. Assumes `dist` is symmetric.
+///
+/// A double bridge needs at least 8 nodes, so for smaller `n` the `kicks` are skipped and the
+/// result is just the local search.
+pub fn tsp_heuristic(n: u16, dist: impl Fn(u16, u16) -> i64, kicks: usize, seed: u64) -> Route {
+    let d = Dist::new(n, dist);
+    let mut best = nearest_neighbor_order(&d);
+    local_search(&d, &mut best);
+    let mut best_cost = d.tour_cost(&best);
+    if n >= 8 {
+        let mut rng = Rng(seed);
+        for _ in 0..kicks {
+            let mut order = double_bridge(&best, &mut rng);
+            local_search(&d, &mut order);
+            let cost = d.tour_cost(&order);
+            if cost < best_cost {
+                best = mem::take(&mut order);
+                best_cost = cost;
+            }
+        }
+    }
+    d.route(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIST: [[i64; 4]; 4] = [
+        [0, 10, 15, 20], //
+        [5, 0, 9, 10],
+        [6, 13, 0, 12],
+        [8, 8, 9, 0],
+    ];
+
+    #[test]
+    fn test_tsp() {
+        let r = tsp(4, |a, b| DIST[a as usize][b as usize]);
+        assert_eq!(r, Route::new(vec![0, 1, 3, 2], 35));
+        let r = inv_tsp(4, |a, b| DIST[a as usize][b as usize]);
+        assert_eq!(r, Route::new(vec![0, 3, 2, 1], 47));
+    }
+
+    #[test]
+    fn test_tsp_small() {
+        let dist = |a: u16, b: u16| DIST[a as usize][b as usize];
+        assert_eq!(tsp(0, dist), Route::new(vec![], 0));
+        assert_eq!(tsp(1, dist), Route::new(vec![0], 0));
+        assert_eq!(tsp(2, dist), Route::new(vec![0, 1], 15));
+        assert_eq!(tsp(3, |_, _| i64::MAX / 4).cost, 3 * (i64::MAX / 4));
+    }
+
+    #[test]
+    fn test_hamiltonian_path() {
+        let dist = |a: u16, b: u16| DIST[a as usize][b as usize];
+        assert_eq!(
+            hamiltonian_path(4, 0, dist),
+            Route::new(vec![0, 1, 3, 2], 29)
+        );
+        assert_eq!(
+            hamiltonian_path(4, 2, dist),
+            Route::new(vec![2, 3, 1, 0], 25)
+        );
+        assert_eq!(
+            hamiltonian_path_to(4, 0, 3, dist),
+            Route::new(vec![0, 1, 2, 3], 31)
+        );
+        assert_eq!(hamiltonian_path(1, 0, dist), Route::new(vec![0], 0));
+        assert_eq!(
+            hamiltonian_path_to(2, 1, 0, dist),
+            Route::new(vec![1, 0], 5)
+        );
+    }
+
+    /// points of a 7x6 grid, ordered by column.
+    fn grid_dist(a: u16, b: u16) -> i64 {
+        let (ax, ay) = ((a / 6) as i64, (a % 6) as i64);
+        let (bx, by) = ((b / 6) as i64, (b % 6) as i64);
+        (ax - bx).abs() + (ay - by).abs()
+    }
+
+    #[test]
+    fn test_tsp_heuristic() {
+        let scattered = |a: u16, b: u16| {
+            let p = |k: u16| ((k as i64 * 37) % 23, (k as i64 * 53) % 29);
+            let ((ax, ay), (bx, by)) = (p(a), p(b));
+            (ax - bx).abs() + (ay - by).abs()
+        };
+        let exact = tsp(12, scattered);
+        let nn = tsp_nearest_neighbor(12, scattered);
+        let improved = tsp_improve(nn.clone(), scattered);
+        let heuristic = tsp_heuristic(12, scattered, 50, 1);
+        assert!(nn.cost >= improved.cost);
+        assert!(improved.cost >= heuristic.cost);
+        assert_eq!(heuristic.cost, exact.cost);
+        assert_eq!(heuristic.order[0], 0);
+
+        let r = tsp_heuristic(42, grid_dist, 100, 1);
+        assert_eq!(r.cost, 42);
+        let mut order = r.order.clone();
+        order.sort();
+        assert_eq!(order, (0..42).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,215 @@
+use crate::grid::{Grid, Pos, idx};
+use std::collections::HashSet;
+
+/// Disjoint sets over `0..n`, with path halving and union by size.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl UnionFind {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            count: n,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn find(&mut self, mut a: usize) -> usize {
+        while self.parent[a] != a {
+            self.parent[a] = self.parent[self.parent[a]];
+            a = self.parent[a];
+        }
+        a
+    }
+
+    /// merges the sets of `a` and `b`, returns false if they already were the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            (a, b) = (b, a);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.count -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// size of the set containing `a`.
+    pub fn size_of(&mut self, a: usize) -> usize {
+        let a = self.find(a);
+        self.size[a]
+    }
+}
+
+fn union_neighbors<Cell>(
+    sets: &mut UnionFind,
+    grid: &Grid<Cell>,
+    p @ [x, y]: Pos,
+    mut connected: impl FnMut(Pos, Pos) -> bool,
+) {
+    for n in [[x + 1, y], [x, y + 1], [x - 1, y], [x, y - 1]] {
+        if grid.is_inside(n) && connected(p, n) {
+            sets.union(idx(p, grid.size), idx(n, grid.size));
+        }
+    }
+}
+
+/// Labels the 4-connected regions of cells for which `connected` holds between neighbors, numbered
+/// from 0 in order of their first cell. Returns the labels and the number of regions.
+pub fn label_regions<Cell>(
+    grid: &Grid<Cell>,
+    connected: impl Fn(&Cell, &Cell) -> bool,
+) -> (Grid<usize>, usize) {
+    let mut sets = UnionFind::new(grid.len());
+    for p in grid.positions() {
+        union_neighbors(&mut sets, grid, p, |a, b| connected(&grid[a], &grid[b]));
+    }
+    let mut labels = vec![usize::MAX; grid.len()];
+    let mut count = 0;
+    let cells = (0..grid.len())
+        .map(|i| {
+            let root = sets.find(i);
+            if labels[root] == usize::MAX {
+                labels[root] = count;
+                count += 1;
+            }
+            labels[root]
+        })
+        .collect();
+    (
+        Grid {
+            cells,
+            size: grid.size,
+        },
+        count,
+    )
+}
+
+/// Blocks the cells in `insertions` one after another and returns the index of the first
+/// insertion after which `goal` can no longer be reached from `start` through open cells, or
+/// `None` if it can always be reached.
+///
+/// Works offline: starts from the fully blocked grid and removes the insertions in reverse, so
+/// every cell is only merged into the [UnionFind] once. Panics if `goal` cannot be reached from
+/// `start` even without any insertions.
+pub fn first_disconnecting_insertion<Cell>(
+    grid: &Grid<Cell>,
+    is_open: impl Fn(Pos, &Cell) -> bool,
+    insertions: &[Pos],
+    start: Pos,
+    goal: Pos,
+) -> Option<usize> {
+    let mut open = Grid {
+        cells: grid.iter_pos().map(|(p, c)| is_open(p, c)).collect(),
+        size: grid.size,
+    };
+    let (s, g) = (idx(start, grid.size), idx(goal, grid.size));
+    let mut sets = UnionFind::new(grid.len());
+    for p in grid.positions() {
+        if open[p] {
+            union_neighbors(&mut sets, grid, p, |_, n| open[n]);
+        }
+    }
+    assert!(
+        open[start] && open[goal] && sets.same(s, g),
+        "start and goal are not connected even without insertions"
+    );
+
+    let mut first_insertion = Grid::new_with(grid.size, || usize::MAX);
+    let mut seen = HashSet::new();
+    for (i, &p) in insertions.iter().enumerate() {
+        if seen.insert(p) {
+            first_insertion[p] = i;
+            open[p] = false;
+        }
+    }
+
+    let mut sets = UnionFind::new(grid.len());
+    for p in grid.positions() {
+        if open[p] {
+            union_neighbors(&mut sets, grid, p, |_, n| open[n]);
+        }
+    }
+    if open[start] && open[goal] && sets.same(s, g) {
+        return None;
+    }
+
+    for (i, &p) in insertions.iter().enumerate().rev() {
+        if first_insertion[p] != i || !is_open(p, &grid[p]) {
+            continue;
+        }
+        open[p] = true;
+        union_neighbors(&mut sets, grid, p, |_, n| open[n]);
+        if open[start] && open[goal] && sets.same(s, g) {
+            return Some(i);
+        }
+    }
+    unreachable!("all insertions are undone, and start and goal were connected without them")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.same(0, 3));
+        assert!(!sets.same(0, 4));
+        assert_eq!(sets.size_of(2), 4);
+        assert_eq!(sets.count(), 3);
+    }
+
+    #[test]
+    fn test_label_regions() {
+        let grid = Grid::from([*b"AAAA", *b"BBCD", *b"BBCC", *b"EEEC"]);
+        let (labels, count) = label_regions(&grid, |a, b| a == b);
+        assert_eq!(count, 5);
+        assert_eq!(labels.to_string(), "0000\n1123\n1122\n4442");
+    }
+
+    #[test]
+    fn test_first_disconnecting_insertion() {
+        let grid = Grid::<bool>::new([3, 3]);
+        let walls = [[1, 0], [1, 0], [1, 1], [2, 2], [1, 2]];
+        let first = first_disconnecting_insertion(&grid, |_, &b| !b, &walls, [0, 0], [2, 0]);
+        assert_eq!(first, Some(3));
+        let first = first_disconnecting_insertion(&grid, |_, &b| !b, &walls[..3], [0, 0], [2, 0]);
+        assert_eq!(first, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "not connected even without insertions")]
+    fn test_first_disconnecting_insertion_disconnected() {
+        let grid = Grid::from([[false, true, false]]);
+        first_disconnecting_insertion(&grid, |_, &b| !b, &[], [0, 0], [2, 0]);
+    }
+}
@@ -0,0 +1,213 @@
+use super::{Cost, Path, a_star_rev};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+use std::hash::Hash;
+
+struct YenIter<Node, Edge, C, IsGoal, GetNeighbors, Heuristic, Distance> {
+    start: Node,
+    is_goal: IsGoal,
+    get_neighbors: GetNeighbors,
+    heuristic: Heuristic,
+    distance: Distance,
+    accepted: Vec<Path<Node, Edge, C>>,
+    candidates: Vec<Option<Path<Node, Edge, C>>>,
+    queue: BinaryHeap<Reverse<(C, usize)>>,
+    seen: HashSet<Vec<Node>>,
+    done: bool,
+}
+
+impl<Node, Edge, C, Neighbors, IsGoal, GetNeighbors, Heuristic, Distance>
+    YenIter<Node, Edge, C, IsGoal, GetNeighbors, Heuristic, Distance>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    IsGoal: Fn(&Node) -> bool,
+    GetNeighbors: Fn(&Node) -> Neighbors,
+    C: Cost,
+    Heuristic: Fn(&Node) -> C,
+    Distance: Fn(&Node, &Edge, &Node) -> C,
+{
+    /// shortest path from `from` that avoids `removed_nodes` and the `removed_edges` leaving
+    /// `from`, with `base_cost` added to every cost.
+    fn search(
+        &self,
+        from: &Node,
+        base_cost: C,
+        removed_nodes: &HashSet<Node>,
+        removed_edges: &HashSet<Node>,
+    ) -> Option<Path<Node, Edge, C>> {
+        let (rev, goal) = a_star_rev(
+            from,
+            &self.is_goal,
+            |n| {
+                let is_from = n == from;
+                (self.get_neighbors)(n)
+                    .into_iter()
+                    .filter(|(m, _)| !removed_nodes.contains(m))
+                    .filter(|(m, _)| !is_from || !removed_edges.contains(m))
+                    .collect::<Vec<_>>()
+            },
+            &self.heuristic,
+            &self.distance,
+        )
+        .ok()?;
+        Some(Path::from_rev_with_base(
+            rev,
+            goal,
+            base_cost,
+            &self.distance,
+        ))
+    }
+
+    fn push_candidates(&mut self) {
+        let prev = self.accepted.last().unwrap();
+        let mut new_candidates = Vec::new();
+        for i in 0..prev.nodes.len() - 1 {
+            let root = &prev.nodes[..=i];
+            let spur = &prev.nodes[i];
+            let removed_edges: HashSet<Node> = self
+                .accepted
+                .iter()
+                .filter(|p| p.nodes.len() > i + 1 && p.nodes[..=i] == *root)
+                .map(|p| p.nodes[i + 1].clone())
+                .collect();
+            let removed_nodes: HashSet<Node> = root[..i].iter().cloned().collect();
+            let Some(spur_path) = self.search(spur, prev.costs[i], &removed_nodes, &removed_edges)
+            else {
+                continue;
+            };
+            let mut nodes = root[..i].to_vec();
+            nodes.extend(spur_path.nodes);
+            if self.seen.contains(&nodes) {
+                continue;
+            }
+            let mut edges = prev.edges[..i].to_vec();
+            edges.extend(spur_path.edges);
+            let mut costs = prev.costs[..i].to_vec();
+            costs.extend(spur_path.costs);
+            self.seen.insert(nodes.clone());
+            new_candidates.push(Path {
+                nodes,
+                edges,
+                costs,
+            });
+        }
+        for c in new_candidates {
+            self.queue.push(Reverse((c.cost(), self.candidates.len())));
+            self.candidates.push(Some(c));
+        }
+    }
+}
+
+impl<Node, Edge, C, Neighbors, IsGoal, GetNeighbors, Heuristic, Distance> Iterator
+    for YenIter<Node, Edge, C, IsGoal, GetNeighbors, Heuristic, Distance>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    IsGoal: Fn(&Node) -> bool,
+    GetNeighbors: Fn(&Node) -> Neighbors,
+    C: Cost,
+    Heuristic: Fn(&Node) -> C,
+    Distance: Fn(&Node, &Edge, &Node) -> C,
+{
+    type Item = Path<Node, Edge, C>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if self.accepted.is_empty() {
+            let Some(first) = self.search(&self.start, C::zero(), &HashSet::new(), &HashSet::new())
+            else {
+                self.done = true;
+                return None;
+            };
+            self.seen.insert(first.nodes.clone());
+            self.accepted.push(first);
+        } else {
+            self.push_candidates();
+            let Some(Reverse((_, i))) = self.queue.pop() else {
+                self.done = true;
+                return None;
+            };
+            let next = self.candidates[i].take().unwrap();
+            self.accepted.push(next);
+        }
+        self.accepted.last().cloned()
+    }
+}
+
+/// Yen's algorithm: lazily yields loopless paths from `start` to a goal in ascending order of
+/// cost, so `.take(k)` gives the k best routes and `.take_while(|p| p.cost() <= budget)` gives all
+/// routes within a budget. Paths are distinguished by their node sequence; ties are yielded in
+/// the order they were discovered.
+///
+/// Every path is found with [a_star_rev], so the same requirements on `heuristic` apply.
+pub fn k_shortest_paths<Node, Edge, Neighbors, C>(
+    start: &Node,
+    is_goal: impl Fn(&Node) -> bool,
+    get_neighbors: impl Fn(&Node) -> Neighbors,
+    heuristic: impl Fn(&Node) -> C,
+    distance: impl Fn(&Node, &Edge, &Node) -> C,
+) -> impl Iterator<Item = Path<Node, Edge, C>>
+where
+    Node: Clone + Eq + Hash,
+    Edge: Clone,
+    Neighbors: IntoIterator<Item = (Node, Edge)>,
+    C: Cost,
+{
+    YenIter {
+        start: start.clone(),
+        is_goal,
+        get_neighbors,
+        heuristic,
+        distance,
+        accepted: Vec::new(),
+        candidates: Vec::new(),
+        queue: BinaryHeap::new(),
+        seen: HashSet::new(),
+        done: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_k_shortest_paths() {
+        // C D E F G H
+        let edges: [&[(usize, i64)]; 6] = [
+            &[(1, 3), (2, 2)],         // C
+            &[(3, 4)],                 // D
+            &[(1, 1), (3, 2), (4, 3)], // E
+            &[(4, 2), (5, 1)],         // F
+            &[(5, 2)],                 // G
+            &[],                       // H
+        ];
+        let goal = 5;
+        let paths: Vec<_> = k_shortest_paths(
+            &0,
+            |&n| n == goal,
+            |&n| edges[n].iter().copied(),
+            |_| 0,
+            |_, &c, _| c,
+        )
+        .map(|p| (p.nodes().to_vec(), p.cost()))
+        .collect();
+        assert_eq!(
+            paths,
+            [
+                (vec![0, 2, 3, 5], 5),
+                (vec![0, 2, 4, 5], 7),
+                (vec![0, 1, 3, 5], 8),
+                (vec![0, 2, 3, 4, 5], 8),
+                (vec![0, 2, 1, 3, 5], 8),
+                (vec![0, 1, 3, 4, 5], 11),
+                (vec![0, 2, 1, 3, 4, 5], 11),
+            ],
+        );
+    }
+}